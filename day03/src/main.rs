@@ -1,6 +1,5 @@
-use std::collections::HashSet;
-
 use aoc::aoc;
+use utils::bitset::CharSet;
 
 #[aoc(2022, 3, 1)]
 fn main(input: &str) -> u32 {
@@ -9,10 +8,8 @@ fn main(input: &str) -> u32 {
         .map(|sack| {
             let mid = sack.len() / 2;
             let (comp1, comp2) = sack.split_at(mid);
-            let comp1 = comp1.chars().collect::<HashSet<_>>();
-            let comp2 = comp2.chars().collect::<HashSet<_>>();
-            let mut intersection = comp1.intersection(&comp2);
-            let common_item = *intersection.next().unwrap();
+            let intersection = CharSet::from(comp1) & CharSet::from(comp2);
+            let common_item = intersection.first().unwrap();
 
             priority(common_item)
         })
@@ -1,19 +1,16 @@
-use std::collections::btree_set::Intersection;
-use std::collections::HashSet;
-
 use aoc::aoc;
 use itertools::Itertools;
+use utils::bitset::CharSet;
 
 #[aoc(2022, 3, 2)]
 fn main(input: &str) -> u32 {
     input
         .lines()
-        .map(|sack| sack.chars().collect::<HashSet<_>>())
+        .map(CharSet::from)
         .tuples()
         .map(|(sack1, sack2, sack3)| {
-            let sack12_intersection = sack1.intersection(&sack2).copied().collect::<HashSet<_>>();
-            let mut intersection = sack12_intersection.intersection(&sack3);
-            let common_item = *intersection.next().unwrap();
+            let intersection = sack1 & sack2 & sack3;
+            let common_item = intersection.first().unwrap();
 
             priority(common_item)
         })
@@ -1,40 +1,16 @@
 use aoc::aoc;
+use utils::{interval::Interval, parse_lines, try_scan, ParseError};
 
 #[aoc(2022, 4, 1)]
 fn main(input: &str) -> usize {
-    input
-        .lines()
-        .map(|pair| {
-            let (range1, range2) = pair.split_once(',').unwrap();
-            let range1 = Range::parse(range1);
-            let range2 = Range::parse(range2);
-
-            (range1, range2)
-        })
-        .filter(|(range1, range2)| range1.contains_range(range2) || range2.contains_range(range1))
+    parse_lines(input, parse_pair)
+        .into_iter()
+        .filter(|(range1, range2)| range1.contains_interval(range2) || range2.contains_interval(range1))
         .count()
 }
 
-struct Range {
-    start: u32,
-    end: u32,
-}
-
-impl Range {
-    fn parse(s: &str) -> Self {
-        let (start, end) = s.split_once('-').unwrap();
-
-        Self {
-            start: start.parse::<u32>().unwrap(),
-            end: end.parse::<u32>().unwrap(),
-        }
-    }
-
-    fn contains(&self, value: u32) -> bool {
-        self.start <= value && value <= self.end
-    }
+fn parse_pair(s: &str) -> Result<(Interval<u32>, Interval<u32>), ParseError> {
+    let (start1, last1, start2, last2) = try_scan!("{}-{},{}-{}", s => u32, u32, u32, u32)?;
 
-    fn contains_range(&self, other: &Self) -> bool {
-        self.contains(other.start) && self.contains(other.end)
-    }
+    Ok((Interval::inclusive(start1, last1), Interval::inclusive(start2, last2)))
 }
@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
 
 use aoc::aoc;
-use utils::{re, StrExt};
+use utils::{re, FromRegex, StrExt};
 
 #[aoc(2022, 5, 2)]
 fn main(input: &str) -> String {
@@ -9,7 +9,7 @@ fn main(input: &str) -> String {
 
     let layers = stacks.lines().map(parse_layer);
     let mut stacks = merge_layers(layers);
-    let instructions = instructions.lines().map(Instruction::parse);
+    let instructions = instructions.lines().map(|line| line.parse::<Instruction>().unwrap());
 
     for instruction in instructions {
         let stack = stacks.get_mut(&instruction.from).unwrap();
@@ -59,20 +59,10 @@ fn merge_layers(layers: impl Iterator<Item = Vec<Option<char>>>) -> BTreeMap<usi
     stacks
 }
 
+#[derive(FromRegex)]
+#[re(r"move (?P<amount>\d+) from (?P<from>\d+) to (?P<to>\d+)")]
 struct Instruction {
     amount: usize,
     from: usize,
     to: usize,
 }
-
-impl Instruction {
-    fn parse(s: &str) -> Self {
-        let cap = re!(r"move (\d+) from (\d+) to (\d+)").captures(s).unwrap();
-
-        Self {
-            amount: cap[1].usize(),
-            from: cap[2].usize(),
-            to: cap[3].usize(),
-        }
-    }
-}
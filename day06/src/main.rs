@@ -1,13 +1,15 @@
-use std::collections::HashSet;
-
 use aoc::aoc;
-use itertools::Itertools;
+use utils::bitset::DistinctWindow;
 
 #[aoc(2022, 6, 1)]
 fn main(input: &str) -> usize {
+    let mut window = DistinctWindow::new(4);
+
     input
         .chars()
-        .tuple_windows()
-        .position(|(a, b, c, d)| HashSet::from([a, b, c, d]).len() == 4)
-        .unwrap() + 4
+        .position(|ch| {
+            window.push(ch);
+            window.all_distinct()
+        })
+        .unwrap() + 1
 }
@@ -1,13 +1,15 @@
-use std::collections::HashSet;
-
 use aoc::aoc;
+use utils::bitset::DistinctWindow;
 
 #[aoc(2022, 6, 2)]
 fn main(input: &str) -> usize {
+    let mut window = DistinctWindow::new(14);
+
     input
         .chars()
-        .collect::<Vec<_>>()
-        .windows(14)
-        .position(|chars| chars.iter().collect::<HashSet<_>>().len() == 14)
-        .unwrap() + 14
+        .position(|ch| {
+            window.push(ch);
+            window.all_distinct()
+        })
+        .unwrap() + 1
 }
@@ -1,23 +1,18 @@
-use std::collections::BTreeMap;
-
 use aoc::aoc;
+use utils::tree::Tree;
 
 const FS_SIZE: u64 = 70_000_000;
 const SIZE_NEEDED: u64 = 30_000_000;
 
 #[aoc(2022, 7, 2)]
 fn main(input: &str) -> u64 {
-    let mut outputs = input.lines().map(Output::parse);
-    let mut root = Dir::new();
-
-    reverse_engineer_fs(&mut root, &mut outputs);
-
-    let root_total = walk_dir_sizes(&root, &mut |_| ());
+    let fs = reverse_engineer_fs(input.lines().map(Output::parse));
+    let root_total = walk_dir_sizes(&fs, |_| ());
     let free_space = FS_SIZE - root_total;
     let need_to_free = SIZE_NEEDED - free_space;
     let mut to_free = FS_SIZE;
 
-    walk_dir_sizes(&root, &mut |size| if need_to_free <= size && size < to_free {
+    walk_dir_sizes(&fs, |size| if need_to_free <= size && size < to_free {
         to_free = size;
     });
 
@@ -54,82 +49,44 @@ impl Output {
     }
 }
 
-fn reverse_engineer_fs(dir: &mut Dir, outputs: &mut impl Iterator<Item = Output>) {
-    while let Some(entry) = outputs.next() {
-        match entry {
-            Output::Cd(name) => {
-                if name == "/" {
-                    continue;
-                }
-
-                if name == ".." {
-                    return;
-                }
+fn reverse_engineer_fs(outputs: impl Iterator<Item = Output>) -> Tree<Entry> {
+    let mut fs = Tree::new(Entry::Dir);
+    let mut cwd = fs.root();
 
-                reverse_engineer_fs(dir.cd_mut(&name), outputs);
+    for output in outputs {
+        match output {
+            Output::Cd(path) => {
+                cwd = fs
+                    .navigate(cwd, &path)
+                    .unwrap_or_else(|| panic!("dir does not exist: {path:?}"));
             }
             Output::Ls => continue,
-            Output::Dir(name) => dir.mkdir(&name),
-            Output::File(name, size) => dir.touch(&name, size),
-        }
-    }
-}
-
-fn walk_dir_sizes(dir: &Dir, f: &mut impl FnMut(u64)) -> u64 {
-    let mut total = 0;
-
-    for (_, entry) in dir.entries() {
-        match entry {
-            Entry::Dir(dir) => total += walk_dir_sizes(dir, f),
-            Entry::File(size) => total += size,
+            Output::Dir(name) => {
+                fs.insert(cwd, &name, Entry::Dir);
+            }
+            Output::File(name, size) => {
+                fs.insert(cwd, &name, Entry::File(size));
+            }
         }
     }
 
-    f(total);
-
-    total
-}
-
-#[derive(Debug)]
-struct Dir {
-    entries: BTreeMap<String, Entry>,
+    fs
 }
 
-impl Dir {
-    fn new() -> Self {
-        Self {
-            entries: BTreeMap::new(),
+/// Calls `f` with the total size of every dir, returning the size of the root
+fn walk_dir_sizes(fs: &Tree<Entry>, mut f: impl FnMut(u64)) -> u64 {
+    fs.fold(fs.root(), |_, entry, child_sizes| match entry {
+        Entry::Dir => {
+            let total = child_sizes.iter().sum();
+            f(total);
+            total
         }
-    }
-
-    fn cd_mut(&mut self, name: &str) -> &mut Dir {
-        let entry = self
-            .entries
-            .get_mut(name)
-            .unwrap_or_else(|| panic!("dir does not exist: {name:?}"));
-
-        let Entry::Dir(dir) = entry else {
-            panic!("not a dir: {name:?}");
-        };
-
-        dir
-    }
-
-    fn entries(&self) -> impl Iterator<Item = (&str, &Entry)> {
-        self.entries.iter().map(|(name, entry)| (&**name, entry))
-    }
-
-    fn mkdir(&mut self, name: &str) {
-        self.entries.insert(name.into(), Entry::Dir(Dir::new()));
-    }
-
-    fn touch(&mut self, name: &str, size: u64) {
-        self.entries.insert(name.into(), Entry::File(size));
-    }
+        Entry::File(size) => *size,
+    })
 }
 
 #[derive(Debug)]
 enum Entry {
-    Dir(Dir),
+    Dir,
     File(u64),
 }
@@ -3,9 +3,11 @@ use std::mem;
 
 use aoc::aoc;
 use itertools::Itertools;
-use num::integer::lcm;
 use pest::Parser as _;
 use pest_derive::Parser;
+use utils::modular::{lcm, Dynamic, ModInt};
+
+type Worry = ModInt<Dynamic>;
 
 #[aoc(2022, 11, 2)]
 fn main(input: &str) -> usize {
@@ -13,6 +15,15 @@ fn main(input: &str) -> usize {
         .map(Monkey::parse)
         .collect::<Vec<_>>();
 
+    // Throws target monkeys by id, so each monkey is kept at the index of its id
+    monkeys.sort_by_key(|monkey| monkey.id);
+
+    assert!(
+        monkeys.iter().enumerate().all(|(i, monkey)| monkey.id == i),
+        "monkey ids aren't 0..{}",
+        monkeys.len(),
+    );
+
     let lcm = monkeys.iter()
         .map(|monkey| monkey.test)
         .reduce(lcm)
@@ -30,7 +41,7 @@ fn main(input: &str) -> usize {
         .product()
 }
 
-fn do_round(monkeys: &mut [Monkey], lcm: i64) {
+fn do_round(monkeys: &mut [Monkey], lcm: u64) {
     for i in 0..monkeys.len() {
         let throws = monkeys[i].do_inspections(lcm);
 
@@ -47,9 +58,9 @@ fn distribute_throws(throws: Vec<Throw>, monkeys: &mut [Monkey]) {
 #[derive(Debug)]
 struct Monkey {
     id: usize,
-    items: Vec<i64>,
+    items: Vec<u64>,
     op: Op,
-    test: i64,
+    test: u64,
     on_true: usize,
     on_false: usize,
     num_inspects: usize,
@@ -65,18 +76,18 @@ impl Monkey {
             .next()
             .unwrap()
             .into_inner()
-            .map(|item| item.as_str().parse::<i64>().unwrap())
+            .map(|item| item.as_str().parse::<u64>().unwrap())
             .collect::<Vec<_>>();
 
         let op = pairs.next().unwrap();
         let op = match op.as_rule() {
             Rule::op_add => {
-                let value = op.into_inner().next().unwrap().as_str().parse::<i64>().unwrap();
+                let value = op.into_inner().next().unwrap().as_str().parse::<u64>().unwrap();
 
                 Op::Add(value)
             },
             Rule::op_mul => {
-                let value = op.into_inner().next().unwrap().as_str().parse::<i64>().unwrap();
+                let value = op.into_inner().next().unwrap().as_str().parse::<u64>().unwrap();
 
                 Op::Mul(value)
             },
@@ -84,7 +95,7 @@ impl Monkey {
             _ => unreachable!(),
         };
 
-        let test = pairs.next().unwrap().as_str().parse::<i64>().unwrap();
+        let test = pairs.next().unwrap().as_str().parse::<u64>().unwrap();
         let on_true = pairs.next().unwrap().as_str().parse::<usize>().unwrap();
         let on_false = pairs.next().unwrap().as_str().parse::<usize>().unwrap();
 
@@ -99,14 +110,16 @@ impl Monkey {
         }
     }
 
-    fn do_inspections(&mut self, lcm: i64) -> Vec<Throw> {
+    fn do_inspections(&mut self, lcm: u64) -> Vec<Throw> {
         let mut throws = Vec::new();
 
-        for mut item in mem::take(&mut self.items) {
+        for item in mem::take(&mut self.items) {
+            let mut item = Worry::new(item, lcm);
+
             self.num_inspects += 1;
             self.op.apply_to(&mut item);
-            item %= lcm;
 
+            let item = item.value();
             let target = if item % self.test == 0 {
                 self.on_true
             } else {
@@ -124,22 +137,22 @@ impl Monkey {
 }
 
 struct Throw {
-    item: i64,
+    item: u64,
     target: usize,
 }
 
 #[derive(Debug)]
 enum Op {
-    Add(i64),
-    Mul(i64),
+    Add(u64),
+    Mul(u64),
     Square,
 }
 
 impl Op {
-    fn apply_to(&self, lhs: &mut i64) {
+    fn apply_to(&self, lhs: &mut Worry) {
         match self {
-            Op::Add(rhs) => *lhs += rhs,
-            Op::Mul(rhs) => *lhs *= rhs,
+            Op::Add(rhs) => *lhs += *rhs,
+            Op::Mul(rhs) => *lhs *= *rhs,
             Op::Square => *lhs *= *lhs,
         }
     }
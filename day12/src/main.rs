@@ -1,29 +1,18 @@
-use std::collections::HashMap;
-
 use aoc::aoc;
-use pathfinding::directed::astar::astar;
+use utils::{grid::Grid, search::bfs};
 
 #[aoc(2022, 12, 1)]
 fn main(input: &str) -> usize {
-    let grid = Grid::parse(input);
-    let start = grid.info.iter().find(|(_, info)| **info == Info::Start)
-        .map(|(pos, _)| pos)
-        .unwrap();
+    let grid = Grid::parse(input, Info::parse);
+    let start = grid.position(|info| *info == Info::Start).unwrap();
 
-    let (_, steps) = astar(
+    bfs(
         start,
-        |pos| grid.climbable_neighbours(*pos).map(|pos| (pos, 1)),
-        |_pos| 1,
-        |pos| grid.info.get(pos) == Some(&Info::End),
-    ).unwrap();
-
-    steps
-}
-
-struct Grid {
-    info: HashMap<(usize, usize), Info>,
-    width: usize,
-    height: usize,
+        |pos| climbable_neighbours(&grid, *pos),
+        |pos| grid[*pos] == Info::End,
+    )
+    .unwrap()
+    .cost
 }
 
 #[derive(PartialEq, Eq, Copy, Clone)]
@@ -55,81 +44,16 @@ impl Info {
     }
 }
 
-impl Grid {
-    fn new() -> Self {
-        Self {
-            info: HashMap::new(),
-            width: 0,
-            height: 0,
-        }
-    }
-
-    fn parse(s: &str) -> Self {
-        s.lines()
-            .enumerate()
-            .flat_map(|(y, line)| line.chars().enumerate().map(move |(x, ch)| ((x, y), ch)))
-            .map(|(pos, ch)| (pos, Info::parse(ch)))
-            .collect::<Grid>()
-    }
-
-    fn set(&mut self, (x, y): (usize, usize), info: Info) {
-        self.width = self.width.max(x + 1);
-        self.height = self.height.max(y + 1);
-        self.info.insert((x, y), info);
-    }
-
-    fn climbable_neighbours(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
-        let info = &self.info[&pos];
+fn climbable_neighbours(grid: &Grid<Info>, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let info = grid[pos];
 
-        [
-            Direction::Up.apply(pos),
-            Direction::Down.apply(pos),
-            Direction::Left.apply(pos),
-            Direction::Right.apply(pos),
-        ]
-        .into_iter()
-        .flatten()
-        .filter(|pos| {
-            let Some(other_info) = self.info.get(pos).copied() else {
-                return false;
-            };
+    grid.neighbours4(pos).filter(move |pos| {
+        let other_info = grid[*pos];
 
-            if info.elevation() < other_info.elevation() && (other_info.elevation() - info.elevation()) > 1 {
-                return false;
-            }
-
-            true
-        })
-    }
-}
-
-impl FromIterator<((usize, usize), Info)> for Grid {
-    fn from_iter<T: IntoIterator<Item = ((usize, usize), Info)>>(iter: T) -> Self {
-        let mut grid = Grid::new();
-
-        for (pos, info) in iter {
-            grid.set(pos, info);
+        if info.elevation() < other_info.elevation() && (other_info.elevation() - info.elevation()) > 1 {
+            return false;
         }
 
-        grid
-    }
-}
-
-#[derive(Copy, Clone)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn apply(&self, (x, y): (usize, usize)) -> Option<(usize, usize)> {
-        Some(match self {
-            Direction::Up => (x, y.checked_sub(1)?),
-            Direction::Down => (x, y + 1),
-            Direction::Left => (x.checked_sub(1)?, y),
-            Direction::Right => (x + 1, y),
-        })
-    }
+        true
+    })
 }
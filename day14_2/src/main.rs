@@ -1,6 +1,192 @@
+use std::fmt;
+
 use aoc::aoc;
+use utils::{grid::Grid, render::Render, scan};
+
+const SOURCE_X: i64 = 500;
 
 #[aoc(2022, 14, 2)]
-fn main(input: &str) -> i32 {
-    unimplemented!()
+fn main(input: &str) -> usize {
+    let mut cave = Cave::parse(input, Bottom::Floor);
+
+    cave.pour();
+    cave.settled()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Cell {
+    Air,
+    Rock,
+    Sand,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Bottom {
+    /// Sand falling below the lowest rock is lost
+    Abyss,
+    /// An endless floor two rows below the lowest rock
+    Floor,
+}
+
+struct Cave {
+    /// Covers every position sand can reach, from `min_x` onwards
+    grid: Grid<Cell>,
+    min_x: i64,
+    bottom: Bottom,
+    /// The positions the current unit of sand fell through, starting at the source.
+    /// The next unit falls along the same path, so it resumes where the previous one settled.
+    path: Vec<(usize, usize)>,
+    settled: usize,
+}
+
+impl Cave {
+    fn parse(input: &str, bottom: Bottom) -> Self {
+        let paths = input
+            .lines()
+            .map(|line| {
+                line.split(" -> ")
+                    .map(|point| scan!("{},{}", point => i64, usize))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let max_y = paths.iter().flatten().map(|&(_, y)| y).max().unwrap();
+        let floor_y = max_y + 2;
+        // Sand spreads at most one column per row, and the padding keeps diagonal moves in bounds.
+        // Rocks may be close enough to x=0 for the padding to go negative.
+        let min_x = paths.iter().flatten().map(|&(x, _)| x).min().unwrap().min(SOURCE_X - floor_y as i64) - 1;
+        let max_x = paths.iter().flatten().map(|&(x, _)| x).max().unwrap().max(SOURCE_X + floor_y as i64) + 1;
+        let mut grid = Grid::new((max_x - min_x + 1) as usize, floor_y, Cell::Air);
+
+        for path in &paths {
+            for segment in path.windows(2) {
+                let ((x1, y1), (x2, y2)) = (segment[0], segment[1]);
+
+                for x in x1.min(x2)..=x1.max(x2) {
+                    for y in y1.min(y2)..=y1.max(y2) {
+                        grid.set(((x - min_x) as usize, y), Cell::Rock);
+                    }
+                }
+            }
+        }
+
+        Self {
+            grid,
+            min_x,
+            bottom,
+            path: Vec::new(),
+            settled: 0,
+        }
+    }
+
+    fn pour(&mut self) {
+        while self.drop_unit() {}
+    }
+
+    /// Lets one unit of sand fall, returning whether it came to rest
+    fn drop_unit(&mut self) -> bool {
+        if self.path.is_empty() {
+            let source = ((SOURCE_X - self.min_x) as usize, 0);
+
+            if self.grid[source] != Cell::Air {
+                return false;
+            }
+
+            self.path.push(source);
+        }
+
+        while let Some(&(x, y)) = self.path.last() {
+            if y + 1 == self.grid.height() {
+                if self.bottom == Bottom::Abyss {
+                    return false;
+                }
+
+                break;
+            }
+
+            let next = [(x, y + 1), (x - 1, y + 1), (x + 1, y + 1)]
+                .into_iter()
+                .find(|&pos| self.grid[pos] == Cell::Air);
+
+            match next {
+                Some(next) => self.path.push(next),
+                None => break,
+            }
+        }
+
+        let pos = self.path.pop().unwrap();
+
+        self.grid.set(pos, Cell::Sand);
+        self.settled += 1;
+
+        true
+    }
+
+    fn settled(&self) -> usize {
+        self.settled
+    }
+}
+
+impl fmt::Display for Cave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let render = Render::new(&self.grid, |cell| match cell {
+            Some(Cell::Rock) => '#',
+            Some(Cell::Sand) => 'o',
+            _ => '.',
+        });
+
+        write!(f, "{}", render)?;
+
+        if self.bottom == Bottom::Floor {
+            writeln!(f, "{}", "#".repeat(self.grid.width()))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::render::assert_picture_eq;
+
+    const EXAMPLE: &str = "\
+498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
+    #[test]
+    fn example() {
+        let mut cave = Cave::parse(EXAMPLE, Bottom::Floor);
+
+        cave.pour();
+
+        assert_eq!(cave.settled(), 93);
+        assert_picture_eq(
+            &cave.to_string(),
+            "
+            ............o............
+            ...........ooo...........
+            ..........ooooo..........
+            .........ooooooo.........
+            ........oo#ooo##o........
+            .......ooo#ooo#ooo.......
+            ......oo###ooo#oooo......
+            .....oooo.oooo#ooooo.....
+            ....oooooooooo#oooooo....
+            ...ooo#########ooooooo...
+            ..ooooo.......ooooooooo..
+            #########################
+            ",
+        );
+    }
+
+    #[test]
+    fn rocks_at_the_left_edge() {
+        let mut cave = Cave::parse("0,5 -> 2,5", Bottom::Floor);
+
+        cave.pour();
+
+        // The sand fills all seven rows above the floor in a triangle, far from the rocks
+        assert_eq!(cave.settled(), 49);
+    }
 }
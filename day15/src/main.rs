@@ -1,6 +1,98 @@
+use std::collections::HashSet;
+
 use aoc::aoc;
+use utils::{
+    geom::Vec2,
+    interval::{Interval, IntervalSet},
+    scan,
+};
+
+/// The row the puzzle asks about
+const ROW: i64 = 2_000_000;
 
 #[aoc(2022, 15, 1)]
-fn main(input: &str) -> i32 {
-    unimplemented!()
+fn main(input: &str) -> i64 {
+    let sensors = input.lines().map(Sensor::parse).collect::<Vec<_>>();
+
+    count_beaconless(&sensors, ROW)
+}
+
+#[derive(Debug)]
+struct Sensor {
+    pos: Vec2<i64>,
+    beacon: Vec2<i64>,
+    /// The distance to the closest beacon, within which no other beacon can be
+    radius: i64,
+}
+
+impl Sensor {
+    fn parse(s: &str) -> Self {
+        let (x, y, beacon_x, beacon_y) = scan!(
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            s => i64, i64, i64, i64
+        );
+        let pos = Vec2::new(x, y);
+        let beacon = Vec2::new(beacon_x, beacon_y);
+
+        Self {
+            pos,
+            beacon,
+            radius: pos.manhattan_distance(beacon),
+        }
+    }
+
+    /// The part of `row` within range of the sensor
+    fn coverage(&self, row: i64) -> Interval<i64> {
+        let reach = self.radius - (self.pos.y - row).abs();
+
+        if reach < 0 {
+            return Interval::empty();
+        }
+
+        Interval::inclusive(self.pos.x - reach, self.pos.x + reach)
+    }
+}
+
+/// Counts the positions on `row` that can't hold a beacon
+fn count_beaconless(sensors: &[Sensor], row: i64) -> i64 {
+    let covered = sensors
+        .iter()
+        .map(|sensor| sensor.coverage(row))
+        .collect::<IntervalSet<_>>();
+
+    let beacons_on_row = sensors
+        .iter()
+        .map(|sensor| sensor.beacon)
+        .filter(|beacon| beacon.y == row && covered.contains(beacon.x))
+        .collect::<HashSet<_>>();
+
+    covered.covered_len() - beacons_on_row.len() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+
+    #[test]
+    fn example() {
+        let sensors = EXAMPLE.lines().map(Sensor::parse).collect::<Vec<_>>();
+
+        assert_eq!(count_beaconless(&sensors, 10), 26);
+    }
 }
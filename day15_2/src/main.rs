@@ -1,6 +1,119 @@
 use aoc::aoc;
+use utils::{geom::Vec2, scan};
+
+/// The distress beacon is within `0..=SEARCH_MAX` on both axes
+const SEARCH_MAX: i64 = 4_000_000;
 
 #[aoc(2022, 15, 2)]
-fn main(input: &str) -> i32 {
-    unimplemented!()
+fn main(input: &str) -> i64 {
+    let sensors = input.lines().map(Sensor::parse).collect::<Vec<_>>();
+    let beacon = find_distress_beacon(&sensors, SEARCH_MAX).expect("no uncovered position");
+
+    tuning_frequency(beacon)
+}
+
+fn tuning_frequency(beacon: Vec2<i64>) -> i64 {
+    beacon.x * 4_000_000 + beacon.y
+}
+
+#[derive(Debug)]
+struct Sensor {
+    pos: Vec2<i64>,
+    /// The distance to the closest beacon, within which no other beacon can be
+    radius: i64,
+}
+
+impl Sensor {
+    fn parse(s: &str) -> Self {
+        let (x, y, beacon_x, beacon_y) = scan!(
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            s => i64, i64, i64, i64
+        );
+        let pos = Vec2::new(x, y);
+
+        Self {
+            pos,
+            radius: pos.manhattan_distance(Vec2::new(beacon_x, beacon_y)),
+        }
+    }
+
+    fn covers(&self, pos: Vec2<i64>) -> bool {
+        self.pos.manhattan_distance(pos) <= self.radius
+    }
+
+    /// The `c` of the lines `y = x + c` that contain the edges just outside the sensor's range
+    fn ascending_boundaries(&self) -> [i64; 2] {
+        let c = self.pos.y - self.pos.x;
+
+        [c - self.radius - 1, c + self.radius + 1]
+    }
+
+    /// The `c` of the lines `y = -x + c` that contain the edges just outside the sensor's range
+    fn descending_boundaries(&self) -> [i64; 2] {
+        let c = self.pos.y + self.pos.x;
+
+        [c - self.radius - 1, c + self.radius + 1]
+    }
+}
+
+/// Finds the only position within the search area that no sensor covers.
+///
+/// That position is hemmed in by sensor ranges, or by the edges of the area, on every side.
+/// So it lies where two of the boundary lines just outside the ranges cross,
+/// where such a line meets an edge of the area, or in a corner of the area.
+fn find_distress_beacon(sensors: &[Sensor], search_max: i64) -> Option<Vec2<i64>> {
+    let ascending = sensors.iter().flat_map(Sensor::ascending_boundaries).collect::<Vec<_>>();
+    let descending = sensors.iter().flat_map(Sensor::descending_boundaries).collect::<Vec<_>>();
+    let max = search_max;
+
+    let intersections = ascending
+        .iter()
+        .flat_map(|&a| descending.iter().map(move |&b| (a, b)))
+        .filter(|(a, b)| (b - a) % 2 == 0)
+        .map(|(a, b)| ((b - a) / 2, (a + b) / 2));
+
+    let edge_crossings = ascending
+        .iter()
+        .flat_map(|&a| [(0, a), (max, max + a), (-a, 0), (max - a, max)])
+        .chain(descending.iter().flat_map(|&b| [(0, b), (max, b - max), (b, 0), (b - max, max)]));
+
+    let corners = [(0, 0), (max, 0), (0, max), (max, max)];
+
+    intersections
+        .chain(edge_crossings)
+        .chain(corners)
+        .map(Vec2::from)
+        .filter(|pos| (0..=max).contains(&pos.x) && (0..=max).contains(&pos.y))
+        .find(|&pos| sensors.iter().all(|sensor| !sensor.covers(pos)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+
+    #[test]
+    fn example() {
+        let sensors = EXAMPLE.lines().map(Sensor::parse).collect::<Vec<_>>();
+
+        let beacon = find_distress_beacon(&sensors, 20);
+
+        assert_eq!(beacon, Some(Vec2::new(14, 11)));
+        assert_eq!(beacon.map(tuning_frequency), Some(56000011));
+    }
 }
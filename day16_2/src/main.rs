@@ -1,6 +1,169 @@
+use std::collections::HashMap;
+
 use aoc::aoc;
+use utils::{re, CapturesExt};
+
+const TIME: u32 = 26;
 
 #[aoc(2022, 16, 2)]
-fn main(input: &str) -> i32 {
-    unimplemented!()
+fn main(input: &str) -> u32 {
+    let network = Network::parse(input);
+
+    max_released_together(&network)
+}
+
+/// The most pressure two agents can release by opening disjoint sets of valves
+fn max_released_together(network: &Network) -> u32 {
+    let best = best_by_opened(network);
+    let all = best.len() - 1;
+
+    // The elephant opens valves the other agent doesn't, so split the valves every possible way
+    (0..best.len())
+        .map(|mine| best[mine] + best[all & !mine])
+        .max()
+        .unwrap()
+}
+
+/// The most pressure a single agent can release by opening at most the valves of each bitmask
+fn best_by_opened(network: &Network) -> Vec<u32> {
+    let num_valves = network.flows.len();
+    let mut best = vec![0; 1 << num_valves];
+    let mut stack = vec![State {
+        valve: network.start,
+        time_left: TIME,
+        opened: 0,
+        released: 0,
+    }];
+
+    while let Some(state) = stack.pop() {
+        let best_released = &mut best[state.opened as usize];
+        *best_released = (*best_released).max(state.released);
+
+        stack.extend(network.successors(&state));
+    }
+
+    // Opening fewer valves than allowed is fine, so carry every result over to its supersets
+    for valve in 0..num_valves {
+        for opened in 0..best.len() {
+            if opened & (1 << valve) != 0 {
+                best[opened] = best[opened].max(best[opened ^ (1 << valve)]);
+            }
+        }
+    }
+
+    best
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    valve: usize,
+    time_left: u32,
+    /// A bit for every valve with a non-zero flow rate
+    opened: u64,
+    /// The pressure released by all opened valves until time runs out
+    released: u32,
+}
+
+/// The valves worth opening, connected by their shortest distances
+#[derive(Debug)]
+struct Network {
+    flows: Vec<u32>,
+    distances: Vec<Vec<u32>>,
+    /// Not one of the valves worth opening, but listed last in `distances`
+    start: usize,
+}
+
+impl Network {
+    fn parse(input: &str) -> Self {
+        let valves = input
+            .lines()
+            .map(|line| {
+                re!(
+                    r"^Valve (?P<name>\w+) has flow rate=(?P<flow>\d+); tunnels? leads? to valves? (?P<tunnels>.+)$",
+                    line,
+                    |cap| (cap.str("name"), cap.parse::<u32>("flow"), cap.str("tunnels").split(", ").collect::<Vec<_>>())
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let indices = valves
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _, _))| (*name, i))
+            .collect::<HashMap<_, _>>();
+
+        // Floyd–Warshall over the full tunnel network
+        let n = valves.len();
+        let mut all_distances = vec![vec![u32::MAX / 2; n]; n];
+
+        for (i, (_, _, tunnels)) in valves.iter().enumerate() {
+            all_distances[i][i] = 0;
+
+            for tunnel in tunnels {
+                all_distances[i][indices[tunnel]] = 1;
+            }
+        }
+
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    all_distances[i][j] = all_distances[i][j].min(all_distances[i][k] + all_distances[k][j]);
+                }
+            }
+        }
+
+        let mut useful = (0..n).filter(|&i| valves[i].1 > 0).collect::<Vec<_>>();
+
+        assert!(useful.len() <= 20, "too many valves to split between two agents");
+
+        useful.push(indices["AA"]);
+
+        Self {
+            flows: useful[..useful.len() - 1].iter().map(|&i| valves[i].1).collect(),
+            distances: useful
+                .iter()
+                .map(|&i| useful.iter().map(|&j| all_distances[i][j]).collect())
+                .collect(),
+            start: useful.len() - 1,
+        }
+    }
+
+    /// The states after walking to an unopened valve and opening it
+    fn successors(&self, state: &State) -> Vec<State> {
+        (0..self.flows.len())
+            .filter(|&valve| state.opened & (1 << valve) == 0)
+            .filter_map(|valve| {
+                let time_left = state.time_left.checked_sub(self.distances[state.valve][valve] + 1)?;
+
+                Some(State {
+                    valve,
+                    time_left,
+                    opened: state.opened | (1 << valve),
+                    released: state.released + self.flows[valve] * time_left,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II";
+
+    #[test]
+    fn example() {
+        assert_eq!(max_released_together(&Network::parse(EXAMPLE)), 1707);
+    }
 }
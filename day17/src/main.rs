@@ -1,6 +1,201 @@
+use std::fmt;
+
 use aoc::aoc;
+use utils::{
+    geom::Vec2,
+    render::{Cells, Render},
+};
+
+const NUM_ROCKS: usize = 2022;
+const WIDTH: usize = 7;
+
+/// The rock shapes in the order they fall, as rows from the bottom up.
+/// Bit 6 is the leftmost column, and every rock starts two columns away from the left wall.
+const ROCKS: [&[u8]; 5] = [
+    &[0b0011110],
+    &[0b0001000, 0b0011100, 0b0001000],
+    &[0b0011100, 0b0000100, 0b0000100],
+    &[0b0010000, 0b0010000, 0b0010000, 0b0010000],
+    &[0b0011000, 0b0011000],
+];
 
 #[aoc(2022, 17, 1)]
-fn main(input: &str) -> i32 {
-    unimplemented!()
+fn main(input: &str) -> usize {
+    let mut chamber = Chamber::new(input);
+
+    for _ in 0..NUM_ROCKS {
+        chamber.drop_rock();
+    }
+
+    chamber.height()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Jet {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone)]
+struct Chamber {
+    /// Settled rock, one bit per column, from the floor up
+    rows: Vec<u8>,
+    jets: Vec<Jet>,
+    next_jet: usize,
+    next_rock: usize,
+}
+
+impl Chamber {
+    fn new(jets: &str) -> Self {
+        let jets = jets
+            .trim()
+            .chars()
+            .map(|ch| match ch {
+                '<' => Jet::Left,
+                '>' => Jet::Right,
+                _ => panic!("invalid jet: {ch:?}"),
+            })
+            .collect();
+
+        Self {
+            rows: Vec::new(),
+            jets,
+            next_jet: 0,
+            next_rock: 0,
+        }
+    }
+
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Lets the next rock fall until it comes to rest
+    fn drop_rock(&mut self) {
+        let mut rock = ROCKS[self.next_rock].to_vec();
+        let mut y = self.height() + 3;
+
+        self.next_rock = (self.next_rock + 1) % ROCKS.len();
+
+        loop {
+            let jet = self.jets[self.next_jet];
+            self.next_jet = (self.next_jet + 1) % self.jets.len();
+
+            if let Some(pushed) = push(&rock, jet).filter(|pushed| self.fits(pushed, y)) {
+                rock = pushed;
+            }
+
+            if y == 0 || !self.fits(&rock, y - 1) {
+                break;
+            }
+
+            y -= 1;
+        }
+
+        for (i, row) in rock.into_iter().enumerate() {
+            if y + i == self.rows.len() {
+                self.rows.push(0);
+            }
+
+            self.rows[y + i] |= row;
+        }
+    }
+
+    /// Whether the rock overlaps no settled rock with its bottom row at `y`
+    fn fits(&self, rock: &[u8], y: usize) -> bool {
+        rock.iter()
+            .enumerate()
+            .all(|(i, row)| self.rows.get(y + i).is_none_or(|settled| settled & row == 0))
+    }
+}
+
+/// Moves a rock one column, unless that would push it into a wall
+fn push(rock: &[u8], jet: Jet) -> Option<Vec<u8>> {
+    match jet {
+        Jet::Left if rock.iter().all(|row| row & 0b1000000 == 0) => Some(rock.iter().map(|row| row << 1).collect()),
+        Jet::Right if rock.iter().all(|row| row & 0b0000001 == 0) => Some(rock.iter().map(|row| row >> 1).collect()),
+        _ => None,
+    }
+}
+
+/// Renders with the top of the tower at `y = 0` and the floor below, at positive `y`
+impl Cells for Chamber {
+    type Cell = ();
+
+    fn bounds(&self) -> Option<(Vec2<i64>, Vec2<i64>)> {
+        let height = self.height() as i64;
+
+        (height > 0).then(|| (Vec2::new(0, 0), Vec2::new(WIDTH as i64 - 1, height - 1)))
+    }
+
+    fn cell(&self, pos: Vec2<i64>) -> Option<&()> {
+        if !(0..WIDTH as i64).contains(&pos.x) {
+            return None;
+        }
+
+        let y = self.height().checked_sub(1)?.checked_sub(usize::try_from(pos.y).ok()?)?;
+        let row = self.rows[y];
+
+        (row & (1 << (WIDTH as i64 - 1 - pos.x)) != 0).then_some(&())
+    }
+}
+
+impl fmt::Display for Chamber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let render = Render::new(self, |rock| if rock.is_some() { '#' } else { '.' });
+
+        write!(f, "{}", render)?;
+        writeln!(f, "{}", "-".repeat(WIDTH))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::render::assert_picture_eq;
+
+    const EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
+    #[test]
+    fn example() {
+        let mut chamber = Chamber::new(EXAMPLE);
+
+        for _ in 0..NUM_ROCKS {
+            chamber.drop_rock();
+        }
+
+        assert_eq!(chamber.height(), 3068);
+    }
+
+    #[test]
+    fn picture() {
+        let mut chamber = Chamber::new(EXAMPLE);
+
+        for _ in 0..10 {
+            chamber.drop_rock();
+        }
+
+        assert_picture_eq(
+            &chamber.to_string(),
+            "
+            ....#..
+            ....#..
+            ....##.
+            ##..##.
+            ######.
+            .###...
+            ..#....
+            .####..
+            ....##.
+            ....##.
+            ....#..
+            ..#.#..
+            ..#.#..
+            #####..
+            ..###..
+            ...#...
+            ..####.
+            -------
+            ",
+        );
+    }
 }
@@ -1,6 +1,72 @@
+use std::collections::HashSet;
+
 use aoc::aoc;
+use utils::{geom::Vec3, scan};
+
+/// The offsets to the six cubes sharing a face with a cube
+const FACES: [Vec3<i32>; 6] = [
+    Vec3::new(1, 0, 0),
+    Vec3::new(-1, 0, 0),
+    Vec3::new(0, 1, 0),
+    Vec3::new(0, -1, 0),
+    Vec3::new(0, 0, 1),
+    Vec3::new(0, 0, -1),
+];
 
 #[aoc(2022, 18, 1)]
-fn main(input: &str) -> i32 {
-    unimplemented!()
+fn main(input: &str) -> usize {
+    let droplet = Droplet::parse(input);
+
+    droplet.surface_area()
+}
+
+#[derive(Debug)]
+struct Droplet {
+    cubes: HashSet<Vec3<i32>>,
+}
+
+impl Droplet {
+    fn parse(input: &str) -> Self {
+        let cubes = input
+            .lines()
+            .map(|line| Vec3::from(scan!("{},{},{}", line => i32, i32, i32)))
+            .collect();
+
+        Self { cubes }
+    }
+
+    /// Counts the faces that don't touch another cube, including those facing air pockets
+    fn surface_area(&self) -> usize {
+        self.cubes
+            .iter()
+            .flat_map(|&cube| FACES.iter().map(move |&face| cube + face))
+            .filter(|neighbour| !self.cubes.contains(neighbour))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5";
+
+    #[test]
+    fn example() {
+        assert_eq!(Droplet::parse("1,1,1\n2,1,1").surface_area(), 10);
+        assert_eq!(Droplet::parse(EXAMPLE).surface_area(), 64);
+    }
 }
@@ -1,6 +1,183 @@
+use std::collections::HashSet;
+
 use aoc::aoc;
+use utils::{geom::Vec3, scan, search::Search};
+
+/// The offsets to the six cubes sharing a face with a cube
+const FACES: [Vec3<i32>; 6] = [
+    Vec3::new(1, 0, 0),
+    Vec3::new(-1, 0, 0),
+    Vec3::new(0, 1, 0),
+    Vec3::new(0, -1, 0),
+    Vec3::new(0, 0, 1),
+    Vec3::new(0, 0, -1),
+];
 
 #[aoc(2022, 18, 2)]
-fn main(input: &str) -> i32 {
-    unimplemented!()
+fn main(input: &str) -> usize {
+    let droplet = Droplet::parse(input);
+    let exterior = droplet.exterior_air();
+    let exterior_area = droplet.faces_towards(&exterior);
+
+    if cfg!(debug_assertions) {
+        let pockets = droplet.air_pockets(&exterior);
+        let surface_area = droplet.surface_area();
+
+        eprintln!("{} of {surface_area} faces touch {} air pockets", surface_area - exterior_area, pockets.len());
+
+        for pocket in &pockets {
+            eprintln!("air pocket of {} cubes at {:?}", pocket.len(), pocket.iter().min().unwrap());
+        }
+    }
+
+    exterior_area
+}
+
+#[derive(Debug)]
+struct Droplet {
+    cubes: HashSet<Vec3<i32>>,
+    /// The corners of a box around the droplet, with a layer of air on every side
+    min: Vec3<i32>,
+    max: Vec3<i32>,
+}
+
+impl Droplet {
+    fn parse(input: &str) -> Self {
+        let cubes = input
+            .lines()
+            .map(|line| Vec3::from(scan!("{},{},{}", line => i32, i32, i32)))
+            .collect::<HashSet<_>>();
+
+        let padding = Vec3::new(1, 1, 1);
+        let min = cubes.iter().copied().reduce(Vec3::component_min).unwrap() - padding;
+        let max = cubes.iter().copied().reduce(Vec3::component_max).unwrap() + padding;
+
+        Self { cubes, min, max }
+    }
+
+    fn in_box(&self, pos: Vec3<i32>) -> bool {
+        self.min.component_min(pos) == self.min && self.max.component_max(pos) == self.max
+    }
+
+    /// The neighbouring positions within the box that are air
+    fn air_neighbours(&self, pos: Vec3<i32>) -> impl Iterator<Item = Vec3<i32>> + '_ {
+        FACES
+            .iter()
+            .map(move |&face| pos + face)
+            .filter(move |&neighbour| self.in_box(neighbour) && !self.cubes.contains(&neighbour))
+    }
+
+    /// Floods the box from a corner, which the padding guarantees to be outside the droplet
+    fn exterior_air(&self) -> HashSet<Vec3<i32>> {
+        Search::new()
+            .bfs_distances(Some(self.min), |&pos| self.air_neighbours(pos))
+            .into_keys()
+            .collect()
+    }
+
+    /// The connected regions of air enclosed by the droplet, each flood filled on its own
+    fn air_pockets(&self, exterior: &HashSet<Vec3<i32>>) -> Vec<HashSet<Vec3<i32>>> {
+        let mut seen = exterior.clone();
+        let mut pockets = Vec::new();
+
+        for x in self.min.x..=self.max.x {
+            for y in self.min.y..=self.max.y {
+                for z in self.min.z..=self.max.z {
+                    let pos = Vec3::new(x, y, z);
+
+                    if self.cubes.contains(&pos) || seen.contains(&pos) {
+                        continue;
+                    }
+
+                    let pocket = Search::new()
+                        .bfs_distances(Some(pos), |&pos| self.air_neighbours(pos))
+                        .into_keys()
+                        .collect::<HashSet<_>>();
+
+                    seen.extend(&pocket);
+                    pockets.push(pocket);
+                }
+            }
+        }
+
+        pockets
+    }
+
+    /// Counts the faces that don't touch another cube, including those facing air pockets
+    fn surface_area(&self) -> usize {
+        self.cubes
+            .iter()
+            .flat_map(|&cube| FACES.iter().map(move |&face| cube + face))
+            .filter(|neighbour| !self.cubes.contains(neighbour))
+            .count()
+    }
+
+    /// Counts the faces that touch the given air
+    fn faces_towards(&self, air: &HashSet<Vec3<i32>>) -> usize {
+        self.cubes
+            .iter()
+            .flat_map(|&cube| FACES.iter().map(move |&face| cube + face))
+            .filter(|neighbour| air.contains(neighbour))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5";
+
+    #[test]
+    fn example() {
+        let droplet = Droplet::parse(EXAMPLE);
+        let exterior = droplet.exterior_air();
+
+        assert_eq!(droplet.surface_area(), 64);
+        assert_eq!(droplet.faces_towards(&exterior), 58);
+    }
+
+    #[test]
+    fn air_pockets() {
+        let droplet = Droplet::parse(EXAMPLE);
+        let exterior = droplet.exterior_air();
+        let pockets = droplet.air_pockets(&exterior);
+
+        assert_eq!(pockets, [HashSet::from([Vec3::new(2, 2, 5)])]);
+
+        // Every exposed face either faces the outside or one of the pockets
+        let pocket_area = pockets.iter().map(|pocket| droplet.faces_towards(pocket)).sum::<usize>();
+
+        assert_eq!(droplet.faces_towards(&exterior) + pocket_area, droplet.surface_area());
+    }
+
+    #[test]
+    fn separate_air_pockets() {
+        // Two hollow 3x3x3 cubes next to each other, each enclosing one cube of air
+        let hollow_cube = |offset: i32| {
+            (0..3)
+                .flat_map(move |x| (0..3).flat_map(move |y| (0..3).map(move |z| (x + offset, y, z))))
+                .filter(|&(x, y, z)| (x - offset, y, z) != (1, 1, 1))
+                .map(|(x, y, z)| format!("{x},{y},{z}"))
+                .collect::<Vec<_>>()
+        };
+        let input = [hollow_cube(0), hollow_cube(3)].concat().join("\n");
+        let droplet = Droplet::parse(&input);
+        let pockets = droplet.air_pockets(&droplet.exterior_air());
+
+        assert_eq!(pockets, [HashSet::from([Vec3::new(1, 1, 1)]), HashSet::from([Vec3::new(4, 1, 1)])]);
+    }
 }
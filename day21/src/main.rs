@@ -1,6 +1,48 @@
 use aoc::aoc;
+use utils::expr::{Expressions, Job};
 
 #[aoc(2022, 21, 1)]
-fn main(input: &str) -> i32 {
-    unimplemented!()
+fn main(input: &str) -> i64 {
+    let monkeys = parse_monkeys(input);
+
+    monkeys.eval::<i64>("root").unwrap()
+}
+
+fn parse_monkeys(input: &str) -> Expressions {
+    input
+        .lines()
+        .map(|line| {
+            let (name, job) = line.split_once(": ").unwrap();
+            let job = job.parse::<Job>().unwrap();
+
+            (name, job)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32";
+
+    #[test]
+    fn example() {
+        assert_eq!(parse_monkeys(EXAMPLE).eval::<i64>("root"), Ok(152));
+    }
 }
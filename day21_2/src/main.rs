@@ -1,6 +1,63 @@
 use aoc::aoc;
+use utils::expr::{Expressions, Job, Rational};
 
 #[aoc(2022, 21, 2)]
-fn main(input: &str) -> i32 {
-    unimplemented!()
+fn main(input: &str) -> i64 {
+    let monkeys = parse_monkeys(input);
+
+    humn_value(&monkeys)
+}
+
+/// The number `humn` must yell for both sides of `root` to be equal
+fn humn_value(monkeys: &Expressions) -> i64 {
+    let (lhs, rhs) = match monkeys.get("root") {
+        Some(Job::Op { lhs, rhs, .. }) => (lhs, rhs),
+        _ => panic!("root must compare two monkeys"),
+    };
+
+    // Rationals keep every division exact, so the path to `humn` can be inverted without guessing
+    monkeys
+        .solve::<Rational>("humn", lhs, rhs)
+        .unwrap()
+        .to_i64()
+        .expect("humn must yell a whole number")
+}
+
+fn parse_monkeys(input: &str) -> Expressions {
+    input
+        .lines()
+        .map(|line| {
+            let (name, job) = line.split_once(": ").unwrap();
+            let job = job.parse::<Job>().unwrap();
+
+            (name, job)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32";
+
+    #[test]
+    fn example() {
+        assert_eq!(humn_value(&parse_monkeys(EXAMPLE)), 301);
+    }
 }
@@ -1,6 +1,42 @@
 use aoc::aoc;
+use utils::radix::Snafu;
 
 #[aoc(2022, 25, 1)]
-fn main(input: &str) -> i32 {
-    unimplemented!()
+fn main(input: &str) -> String {
+    fuel_sum(input).to_string()
+}
+
+fn fuel_sum(input: &str) -> Snafu {
+    input
+        .lines()
+        .map(|line| line.parse::<Snafu>().unwrap())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+1=-0-2
+12111
+2=0=
+21
+2=01
+111
+20012
+112
+1=-1=
+1-12
+12
+1=
+122";
+
+    #[test]
+    fn example() {
+        let sum = fuel_sum(EXAMPLE);
+
+        assert_eq!(sum.to_i64(), Some(4890));
+        assert_eq!(sum.to_string(), "2=-1=0");
+    }
 }
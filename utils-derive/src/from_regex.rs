@@ -0,0 +1,191 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use regex::Regex;
+use syn::{
+    spanned::Spanned, Data, DeriveInput, Error, Field, Fields, GenericArgument, Ident, LitStr,
+    PathArguments, Result, Type,
+};
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let pattern = struct_pattern(&input)?;
+    let regex = Regex::new(&pattern.value())
+        .map_err(|err| Error::new(pattern.span(), err))?;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(input.span(), "FromRegex can only be derived for structs"));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new(data.fields.span(), "FromRegex requires named fields"));
+    };
+
+    let fields = fields.named
+        .iter()
+        .map(|field| expand_field(field, &regex))
+        .collect::<Result<Vec<_>>>()?;
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let expected = format!("a match for {:?}", pattern.value());
+    // The whole text has to match, not just some part of it
+    let anchored = LitStr::new(&format!("^(?:{})$", pattern.value()), pattern.span());
+
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = ::utils::ParseError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                let captures = ::utils::re!(#anchored)
+                    .captures(s)
+                    .ok_or_else(|| ::utils::ParseError::new(s, #expected))?;
+
+                ::std::result::Result::Ok(Self {
+                    #(#fields,)*
+                })
+            }
+        }
+    })
+}
+
+fn struct_pattern(input: &DeriveInput) -> Result<LitStr> {
+    for attr in &input.attrs {
+        if attr.path.is_ident("re") {
+            return attr.parse_args::<LitStr>();
+        }
+    }
+
+    Err(Error::new(input.ident.span(), "missing #[re(\"...\")] attribute"))
+}
+
+fn expand_field(field: &Field, regex: &Regex) -> Result<TokenStream> {
+    let ident = field.ident.as_ref().unwrap();
+    let group = ident.to_string();
+    let group = group.strip_prefix("r#").unwrap_or(&group);
+
+    if !regex.capture_names().flatten().any(|name| name == group) {
+        return Err(Error::new(
+            ident.span(),
+            format!("pattern has no capture group named `{}`", group),
+        ));
+    }
+
+    let separator = field_separator(field)?;
+    let value = match (field_kind(&field.ty), separator) {
+        (FieldKind::Vec(item_ty), Some(separator)) => {
+            let items = parse_items(item_ty, &separator);
+
+            // Empty and non-participating groups are empty lists rather than a single empty item
+            quote! {
+                match captures.name(#group).map(|group| group.as_str()) {
+                    ::std::option::Option::Some(text) if !text.is_empty() => #items,
+                    _ => ::std::vec::Vec::new(),
+                }
+            }
+        }
+        (FieldKind::Vec(_), None) => {
+            return Err(Error::new(
+                field.ty.span(),
+                "Vec fields need a separator, e.g. #[re(sep = \", \")]",
+            ));
+        }
+        (_, Some(separator)) => {
+            return Err(Error::new(separator.span(), "separators are only supported on Vec fields"));
+        }
+        (FieldKind::Option(inner_ty), None) => {
+            let item = parse_item(inner_ty, &quote!(group.as_str()));
+
+            quote! {
+                match captures.name(#group) {
+                    ::std::option::Option::Some(group) => ::std::option::Option::Some(#item),
+                    ::std::option::Option::None => ::std::option::Option::None,
+                }
+            }
+        }
+        (FieldKind::Plain, None) => {
+            let ty = &field.ty;
+
+            quote! {
+                ::utils::CapturesExt::try_parse::<#ty>(&captures, #group)?
+            }
+        }
+    };
+
+    Ok(quote!(#ident: #value))
+}
+
+fn field_separator(field: &Field) -> Result<Option<LitStr>> {
+    let mut separator = None;
+
+    for attr in &field.attrs {
+        if !attr.path.is_ident("re") {
+            continue;
+        }
+
+        attr.parse_args_with(|input: syn::parse::ParseStream| {
+            let key = input.parse::<Ident>()?;
+
+            if key != "sep" {
+                return Err(Error::new(key.span(), "expected `sep`"));
+            }
+
+            input.parse::<syn::Token![=]>()?;
+            separator = Some(input.parse::<LitStr>()?);
+
+            Ok(())
+        })?;
+    }
+
+    Ok(separator)
+}
+
+fn parse_items(item_ty: &Type, separator: &LitStr) -> TokenStream {
+    let item = parse_item(item_ty, &quote!(item));
+
+    quote! {
+        text.split(#separator)
+            .map(|item| ::std::result::Result::<_, ::utils::ParseError>::Ok(#item))
+            .collect::<::std::result::Result<::std::vec::Vec<#item_ty>, _>>()?
+    }
+}
+
+fn parse_item(ty: &Type, text: &TokenStream) -> TokenStream {
+    quote! {{
+        let text: &str = #text;
+
+        text.parse::<#ty>().map_err(|err| {
+            ::utils::ParseError::new(text, ::std::any::type_name::<#ty>()).with_reason(err)
+        })?
+    }}
+}
+
+enum FieldKind<'a> {
+    Plain,
+    Option(&'a Type),
+    Vec(&'a Type),
+}
+
+fn field_kind(ty: &Type) -> FieldKind<'_> {
+    let Type::Path(path) = ty else {
+        return FieldKind::Plain;
+    };
+
+    let Some(segment) = path.path.segments.last() else {
+        return FieldKind::Plain;
+    };
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return FieldKind::Plain;
+    };
+
+    let Some(GenericArgument::Type(inner_ty)) = args.args.first() else {
+        return FieldKind::Plain;
+    };
+
+    if segment.ident == "Option" {
+        FieldKind::Option(inner_ty)
+    } else if segment.ident == "Vec" {
+        FieldKind::Vec(inner_ty)
+    } else {
+        FieldKind::Plain
+    }
+}
@@ -0,0 +1,57 @@
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, Error};
+
+mod from_regex;
+mod re;
+mod scan;
+
+#[proc_macro_derive(FromRegex, attributes(re))]
+pub fn derive_from_regex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    from_regex::expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// `re!(pattern)` yields a lazily compiled `&Regex`, `re!(pattern, text)` the captures of `text`
+/// and `re!(pattern, text, |cap| ...)` hands those captures to the closure.
+///
+/// Literal patterns are compiled at build time. Literal group names passed to
+/// `CapturesExt` methods on the closure's parameter are checked against the pattern,
+/// except where the parameter is shadowed. Only the closure form is checked:
+/// with `let cap = re!(pattern, text)`, unknown group names panic at runtime.
+#[proc_macro]
+pub fn re(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as re::ReInput);
+
+    re::expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// `scan!("move {} from {} to {}", line => usize, usize, usize)` parses each `{}` placeholder
+/// into the corresponding `FromStr` type and returns them as a tuple, panicking on mismatch.
+///
+/// Whitespace in the format matches any run of whitespace in the input.
+#[proc_macro]
+pub fn scan(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as scan::ScanInput);
+
+    scan::expand(input)
+        .map(|scan| quote::quote! {
+            #scan.unwrap_or_else(|err| ::std::panic!("{}", err))
+        })
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Like `scan!`, but returns a `Result<(...), ParseError>`
+#[proc_macro]
+pub fn try_scan(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as scan::ScanInput);
+
+    scan::expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,264 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use regex::Regex;
+use syn::{
+    parse::{Parse, ParseStream},
+    visit::{self, Visit},
+    Arm, Block, Error, Expr, ExprClosure, ExprForLoop, ExprIf, ExprMethodCall, ExprWhile, Ident, Item, Lit,
+    LitStr, Pat, PatIdent, Result, Stmt, Token,
+};
+
+pub struct ReInput {
+    pattern: Expr,
+    text: Option<Expr>,
+    body: Option<ExprClosure>,
+}
+
+impl Parse for ReInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let pattern = input.parse()?;
+        let mut text = None;
+        let mut body = None;
+
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            text = Some(input.parse()?);
+
+            if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+                body = Some(input.parse()?);
+                input.parse::<Option<Token![,]>>()?;
+            }
+        }
+
+        Ok(Self { pattern, text, body })
+    }
+}
+
+pub fn expand(input: ReInput) -> Result<TokenStream> {
+    let pattern = &input.pattern;
+
+    // Patterns that aren't literals can only be checked once they are compiled at runtime
+    if let Some(literal) = literal_pattern(pattern) {
+        let regex = Regex::new(&literal.value())
+            .map_err(|err| Error::new(literal.span(), err))?;
+
+        if let Some(body) = &input.body {
+            check_group_names(&regex, body)?;
+        }
+    }
+
+    let regex = quote! {{
+        ::utils::lazy_static! {
+            static ref RE: ::utils::Regex = ::utils::Regex::new(#pattern).unwrap();
+        }
+
+        &*RE
+    }};
+
+    let Some(text) = &input.text else {
+        return Ok(regex);
+    };
+
+    let captures = quote! {
+        (#regex).captures(#text).unwrap()
+    };
+
+    let Some(body) = &input.body else {
+        return Ok(quote!({ #captures }));
+    };
+
+    Ok(quote! {{
+        fn with_captures<'t, R>(
+            captures: &::utils::Captures<'t>,
+            f: impl FnOnce(&::utils::Captures<'t>) -> R,
+        ) -> R {
+            f(captures)
+        }
+
+        with_captures(&#captures, #body)
+    }})
+}
+
+fn literal_pattern(pattern: &Expr) -> Option<&LitStr> {
+    match pattern {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(lit) => Some(lit),
+            _ => None,
+        },
+        Expr::Group(group) => literal_pattern(&group.expr),
+        Expr::Paren(paren) => literal_pattern(&paren.expr),
+        _ => None,
+    }
+}
+
+fn check_group_names(regex: &Regex, body: &ExprClosure) -> Result<()> {
+    // Without a named parameter there is no way to tell which calls are on the captures
+    let Some(captures) = closure_param(body) else {
+        return Ok(());
+    };
+
+    let mut checker = GroupNameChecker {
+        regex,
+        captures,
+        error: None,
+    };
+
+    checker.visit_expr(&body.body);
+
+    match checker.error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+fn closure_param(closure: &ExprClosure) -> Option<&Ident> {
+    let mut pat = closure.inputs.first()?;
+
+    if let Pat::Type(typed) = pat {
+        pat = &typed.pat;
+    }
+
+    match pat {
+        Pat::Ident(pat) => Some(&pat.ident),
+        _ => None,
+    }
+}
+
+/// Whether `expr` is `ident`, possibly borrowed or in parentheses
+fn is_ident(expr: &Expr, ident: &Ident) -> bool {
+    match expr {
+        Expr::Path(path) => path.qself.is_none() && path.path.is_ident(ident),
+        Expr::Reference(reference) => is_ident(&reference.expr, ident),
+        Expr::Paren(paren) => is_ident(&paren.expr, ident),
+        Expr::Group(group) => is_ident(&group.expr, ident),
+        _ => false,
+    }
+}
+
+/// Whether `pat` binds `ident`, shadowing it
+fn binds(pat: &Pat, ident: &Ident) -> bool {
+    struct Binds<'a> {
+        ident: &'a Ident,
+        found: bool,
+    }
+
+    impl<'ast> Visit<'ast> for Binds<'_> {
+        fn visit_pat_ident(&mut self, pat: &'ast PatIdent) {
+            self.found |= pat.ident == *self.ident;
+            visit::visit_pat_ident(self, pat);
+        }
+    }
+
+    let mut binds = Binds { ident, found: false };
+
+    binds.visit_pat(pat);
+    binds.found
+}
+
+/// Finds `CapturesExt` calls such as `cap.str("name")` on the closure's captures whose group name is a literal.
+///
+/// Stops looking wherever the captures' name is rebound, since calls there are on something else.
+struct GroupNameChecker<'a> {
+    regex: &'a Regex,
+    captures: &'a Ident,
+    error: Option<Error>,
+}
+
+impl<'ast> Visit<'ast> for GroupNameChecker<'_> {
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        let is_captures_ext_method = is_ident(&call.receiver, self.captures)
+            && ["str", "parse", "try_str", "try_parse"]
+                .iter()
+                .any(|method| call.method == method);
+
+        if let (true, Some(Expr::Lit(arg)), 1) = (is_captures_ext_method, call.args.first(), call.args.len()) {
+            if let Lit::Str(name) = &arg.lit {
+                let name_exists = self.regex
+                    .capture_names()
+                    .flatten()
+                    .any(|group| group == name.value());
+
+                if !name_exists {
+                    let error = Error::new(
+                        name.span(),
+                        format!("pattern has no capture group named `{}`", name.value()),
+                    );
+
+                    match &mut self.error {
+                        Some(errors) => errors.combine(error),
+                        None => self.error = Some(error),
+                    }
+                }
+            }
+        }
+
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_block(&mut self, block: &'ast Block) {
+        for stmt in &block.stmts {
+            let Stmt::Local(local) = stmt else {
+                self.visit_stmt(stmt);
+                continue;
+            };
+
+            if let Some((_, init)) = &local.init {
+                self.visit_expr(init);
+            }
+
+            if binds(&local.pat, self.captures) {
+                return;
+            }
+        }
+    }
+
+    fn visit_expr_closure(&mut self, closure: &'ast ExprClosure) {
+        if !closure.inputs.iter().any(|pat| binds(pat, self.captures)) {
+            visit::visit_expr_closure(self, closure);
+        }
+    }
+
+    fn visit_arm(&mut self, arm: &'ast Arm) {
+        if !binds(&arm.pat, self.captures) {
+            visit::visit_arm(self, arm);
+        }
+    }
+
+    fn visit_expr_for_loop(&mut self, for_loop: &'ast ExprForLoop) {
+        self.visit_expr(&for_loop.expr);
+
+        if !binds(&for_loop.pat, self.captures) {
+            self.visit_block(&for_loop.body);
+        }
+    }
+
+    fn visit_expr_if(&mut self, expr_if: &'ast ExprIf) {
+        let Expr::Let(expr_let) = &*expr_if.cond else {
+            return visit::visit_expr_if(self, expr_if);
+        };
+
+        self.visit_expr(&expr_let.expr);
+
+        if !binds(&expr_let.pat, self.captures) {
+            self.visit_block(&expr_if.then_branch);
+        }
+
+        if let Some((_, else_branch)) = &expr_if.else_branch {
+            self.visit_expr(else_branch);
+        }
+    }
+
+    fn visit_expr_while(&mut self, expr_while: &'ast ExprWhile) {
+        let Expr::Let(expr_let) = &*expr_while.cond else {
+            return visit::visit_expr_while(self, expr_while);
+        };
+
+        self.visit_expr(&expr_let.expr);
+
+        if !binds(&expr_let.pat, self.captures) {
+            self.visit_block(&expr_while.body);
+        }
+    }
+
+    /// Nested items can't see the closure's captures
+    fn visit_item(&mut self, _item: &'ast Item) {}
+}
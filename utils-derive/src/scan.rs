@@ -0,0 +1,93 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Error, Expr, LitStr, Result, Token, Type,
+};
+
+pub struct ScanInput {
+    format: LitStr,
+    text: Expr,
+    types: Punctuated<Type, Token![,]>,
+}
+
+impl Parse for ScanInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let format = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let text = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let types = Punctuated::parse_terminated(input)?;
+
+        Ok(Self { format, text, types })
+    }
+}
+
+/// Expands to an expression evaluating to `Result<(T1, T2, ...), ParseError>`
+pub fn expand(input: ScanInput) -> Result<TokenStream> {
+    let ScanInput { format, text, types } = input;
+    let literals = parse_format(&format)?;
+    let num_fields = literals.len() - 1;
+
+    if num_fields != types.len() {
+        return Err(Error::new(
+            format.span(),
+            format!("format has {} placeholders, but {} types were given", num_fields, types.len()),
+        ));
+    }
+
+    let expected = format!("a line matching {:?}", format.value());
+    let fields = (0..num_fields)
+        .map(|i| format_ident!("field{}", i))
+        .collect::<Vec<_>>();
+    let types = types.iter();
+
+    Ok(quote! {
+        ::utils::scan::scan::<#num_fields, _>(
+            #expected,
+            ::std::convert::AsRef::<str>::as_ref(&#text),
+            &[#(#literals),*],
+            |[#(#fields),*]| ::std::result::Result::Ok((
+                #(::utils::scan::parse_field::<#types>(#fields)?,)*
+            )),
+        )
+    })
+}
+
+/// Splits the format string at its `{}` placeholders
+fn parse_format(format: &LitStr) -> Result<Vec<String>> {
+    let value = format.value();
+    let mut literals = vec![String::new()];
+    let mut chars = value.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match (ch, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                literals.last_mut().unwrap().push(ch);
+            }
+            ('{', Some('}')) => {
+                chars.next();
+
+                if literals.len() > 1 && literals.last().unwrap().is_empty() {
+                    return Err(Error::new(
+                        format.span(),
+                        "placeholders must be separated by some text",
+                    ));
+                }
+
+                literals.push(String::new());
+            }
+            ('{', _) | ('}', _) => {
+                return Err(Error::new(
+                    format.span(),
+                    "only `{}` placeholders are supported, use `{{` and `}}` for literal braces",
+                ));
+            }
+            _ => literals.last_mut().unwrap().push(ch),
+        }
+    }
+
+    Ok(literals)
+}
@@ -0,0 +1,113 @@
+use std::{
+    collections::HashSet,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+use utils::bitset::{CharSet, DistinctWindow};
+
+const ITERATIONS: usize = 200;
+
+fn main() {
+    let sacks = rucksacks(300);
+    let stream = datastream(4096);
+
+    println!("{:>24} {:>14} {:>14}", "", "hash set", "bitset");
+
+    let hash_set = time(|| hash_set_common_items(&sacks));
+    let bitset = time(|| char_set_common_items(&sacks));
+
+    println!("{:>24} {:>14?} {:>14?}", "rucksack intersection", hash_set, bitset);
+
+    for &window_size in &[4, 14] {
+        let hash_set = time(|| hash_set_marker(&stream, window_size));
+        let bitset = time(|| distinct_window_marker(&stream, window_size));
+
+        println!("{:>24} {:>14?} {:>14?}", format!("{}-character marker", window_size), hash_set, bitset);
+    }
+}
+
+fn time<T>(mut f: impl FnMut() -> T) -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+
+    start.elapsed() / ITERATIONS as u32
+}
+
+fn hash_set_common_items(sacks: &[String]) -> usize {
+    sacks
+        .iter()
+        .map(|sack| {
+            let (comp1, comp2) = sack.split_at(sack.len() / 2);
+            let comp1 = comp1.chars().collect::<HashSet<_>>();
+            let comp2 = comp2.chars().collect::<HashSet<_>>();
+
+            comp1.intersection(&comp2).count()
+        })
+        .sum()
+}
+
+fn char_set_common_items(sacks: &[String]) -> usize {
+    sacks
+        .iter()
+        .map(|sack| {
+            let (comp1, comp2) = sack.split_at(sack.len() / 2);
+
+            (CharSet::from(comp1) & CharSet::from(comp2)).len()
+        })
+        .sum()
+}
+
+fn hash_set_marker(stream: &[char], window_size: usize) -> Option<usize> {
+    stream
+        .windows(window_size)
+        .position(|chars| chars.iter().collect::<HashSet<_>>().len() == window_size)
+}
+
+fn distinct_window_marker(stream: &[char], window_size: usize) -> Option<usize> {
+    let mut window = DistinctWindow::new(window_size);
+
+    stream.iter().position(|&ch| {
+        window.push(ch);
+        window.all_distinct()
+    })
+}
+
+/// Pseudo-random rucksacks of letters, generated by a xorshift so the benchmark is reproducible
+fn rucksacks(count: usize) -> Vec<String> {
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+
+    (0..count)
+        .map(|_| {
+            let len = 2 * (8 + rng.next() % 20) as usize;
+
+            (0..len).map(|_| rng.letter()).collect()
+        })
+        .collect()
+}
+
+/// A stream that only repeats a few letters, so no marker is found and every window is checked
+fn datastream(len: usize) -> Vec<char> {
+    let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+
+    (0..len).map(|_| (b'a' + (rng.next() % 3) as u8) as char).collect()
+}
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn letter(&mut self) -> char {
+        let letters = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+        letters[(self.next() % letters.len() as u64) as usize] as char
+    }
+}
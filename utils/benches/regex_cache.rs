@@ -0,0 +1,86 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+use regex::Regex;
+use utils::{regex_cache_stats, StrExt};
+
+const PATTERNS: &[&str] = &[
+    r"^move \d+ from \d+ to \d+$",
+    r"^\d+-\d+,\d+-\d+$",
+    r"^[A-Z] [XYZ]$",
+    r"^\$ cd .+$",
+    r"^dir \w+$",
+    r"^(noop|addx -?\d+)$",
+    r"^[a-zA-Z]+$",
+    r"^Sensor at x=-?\d+, y=-?\d+",
+];
+
+const LINES: &[&str] = &[
+    "move 3 from 1 to 2",
+    "2-4,6-8",
+    "A Y",
+    "$ cd /",
+    "dir a",
+    "addx -11",
+    "vJrwpWtwJgWrhcsFMMfFFhFp",
+    "Sensor at x=2, y=18: closest beacon is at x=-2, y=15",
+];
+
+const ITERATIONS_PER_THREAD: usize = 20_000;
+
+fn main() {
+    let thread_counts = [1, 2, 4, 8];
+
+    println!("{:>8} {:>14} {:>14}", "threads", "mutex btree", "sharded");
+
+    for &threads in &thread_counts {
+        let mutex = run(threads, mutex_is_match);
+        let sharded = run(threads, |line, pattern| line.is_match(pattern));
+
+        println!("{:>8} {:>14?} {:>14?}", threads, mutex, sharded);
+    }
+
+    println!("{:?}", regex_cache_stats());
+}
+
+fn run(threads: usize, is_match: fn(&str, &str) -> bool) -> Duration {
+    let start = Instant::now();
+
+    let handles = (0..threads)
+        .map(|_| thread::spawn(move || {
+            let mut matches = 0;
+
+            for i in 0..ITERATIONS_PER_THREAD {
+                let line = LINES[i % LINES.len()];
+                let pattern = PATTERNS[(i / LINES.len()) % PATTERNS.len()];
+
+                matches += is_match(line, pattern) as usize;
+            }
+
+            matches
+        }))
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    start.elapsed()
+}
+
+// The cache as it was before sharding, kept for comparison
+fn mutex_is_match(line: &str, pattern: &str) -> bool {
+    utils::lazy_static! {
+        static ref CACHE: Mutex<BTreeMap<String, Arc<Regex>>> = Mutex::default();
+    }
+
+    let mut cache = CACHE.lock().unwrap();
+    let regex = cache
+        .entry(pattern.into())
+        .or_insert_with(|| Arc::new(Regex::new(pattern).unwrap()));
+
+    regex.is_match(line)
+}
@@ -0,0 +1,301 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    iter::FromIterator,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign},
+};
+
+/// A set of ASCII characters, stored as one bit per character
+#[derive(Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CharSet(u128);
+
+impl CharSet {
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Adds an ASCII character, returning whether it was newly added
+    pub fn insert(&mut self, ch: char) -> bool {
+        let bit = bit(ch);
+        let added = self.0 & bit == 0;
+
+        self.0 |= bit;
+
+        added
+    }
+
+    /// Removes a character, returning whether it was present
+    pub fn remove(&mut self, ch: char) -> bool {
+        let present = self.contains(ch);
+
+        if present {
+            self.0 &= !bit(ch);
+        }
+
+        present
+    }
+
+    pub fn contains(self, ch: char) -> bool {
+        ch.is_ascii() && self.0 & bit(ch) != 0
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The character with the lowest code
+    pub fn first(self) -> Option<char> {
+        self.iter().next()
+    }
+
+    /// The characters in order of their codes
+    pub fn iter(self) -> impl Iterator<Item = char> {
+        let mut bits = self.0;
+
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+
+            let code = bits.trailing_zeros();
+            bits &= bits - 1;
+
+            Some(code as u8 as char)
+        })
+    }
+}
+
+fn bit(ch: char) -> u128 {
+    assert!(ch.is_ascii(), "not an ASCII character: {:?}", ch);
+
+    1 << ch as u32
+}
+
+impl From<&str> for CharSet {
+    fn from(s: &str) -> Self {
+        s.chars().collect()
+    }
+}
+
+impl FromIterator<char> for CharSet {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<char> for CharSet {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        for ch in iter {
+            self.insert(ch);
+        }
+    }
+}
+
+macro_rules! impl_ops {
+    ($($op:ident::$method:ident, $op_assign:ident::$method_assign:ident => $set_method:ident;)*) => {
+        $(
+            impl $op for CharSet {
+                type Output = Self;
+
+                fn $method(self, rhs: Self) -> Self {
+                    self.$set_method(rhs)
+                }
+            }
+
+            impl $op_assign for CharSet {
+                fn $method_assign(&mut self, rhs: Self) {
+                    *self = self.$set_method(rhs);
+                }
+            }
+        )*
+    };
+}
+
+impl_ops! {
+    BitOr::bitor, BitOrAssign::bitor_assign => union;
+    BitAnd::bitand, BitAndAssign::bitand_assign => intersection;
+    Sub::sub, SubAssign::sub_assign => difference;
+}
+
+impl fmt::Debug for CharSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Counts the distinct ASCII characters among the last `size` pushed ones, updating in O(1) per push
+#[derive(Debug, Clone)]
+pub struct DistinctWindow {
+    size: usize,
+    window: VecDeque<u8>,
+    counts: [u32; 128],
+    distinct: usize,
+}
+
+impl DistinctWindow {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            window: VecDeque::with_capacity(size + 1),
+            counts: [0; 128],
+            distinct: 0,
+        }
+    }
+
+    /// Adds a character, dropping the oldest one once the window is full
+    pub fn push(&mut self, ch: char) {
+        assert!(ch.is_ascii(), "not an ASCII character: {:?}", ch);
+
+        let code = ch as u8;
+
+        self.window.push_back(code);
+        self.counts[code as usize] += 1;
+
+        if self.counts[code as usize] == 1 {
+            self.distinct += 1;
+        }
+
+        if self.window.len() > self.size {
+            let oldest = self.window.pop_front().unwrap();
+
+            self.counts[oldest as usize] -= 1;
+
+            if self.counts[oldest as usize] == 0 {
+                self.distinct -= 1;
+            }
+        }
+    }
+
+    pub fn distinct(&self) -> usize {
+        self.distinct
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.window.len() == self.size
+    }
+
+    /// Whether the window is full and contains no character twice
+    pub fn all_distinct(&self) -> bool {
+        self.is_full() && self.distinct == self.size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use proptest::prelude::*;
+
+    /// The number of characters read when the last `size` ones are first all different
+    fn marker_end(s: &str, size: usize) -> Option<usize> {
+        let mut window = DistinctWindow::new(size);
+
+        s.chars()
+            .position(|ch| {
+                window.push(ch);
+                window.all_distinct()
+            })
+            .map(|i| i + 1)
+    }
+
+    #[test]
+    fn char_set() {
+        let mut set = CharSet::from("hello");
+
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.iter().collect::<String>(), "ehlo");
+        assert_eq!(set.first(), Some('e'));
+        assert!(!set.insert('l'));
+        assert!(set.insert('\x7f'));
+        assert!(set.remove('\x7f'));
+        assert!(!set.remove('z'));
+        assert!(!set.contains('é'));
+        assert!(CharSet::new().is_empty());
+        assert_eq!(CharSet::new().first(), None);
+    }
+
+    #[test]
+    fn char_set_operations() {
+        let a = CharSet::from("abc");
+        let b = CharSet::from("bcd");
+
+        assert_eq!(a | b, CharSet::from("abcd"));
+        assert_eq!(a & b, CharSet::from("bc"));
+        assert_eq!(a - b, CharSet::from("a"));
+
+        let mut c = a;
+
+        c -= b;
+        c |= CharSet::from("z");
+        c &= CharSet::from("az");
+        assert_eq!(format!("{:?}", c), "{'a', 'z'}");
+    }
+
+    #[test]
+    #[should_panic(expected = "not an ASCII character")]
+    fn char_set_rejects_non_ascii() {
+        CharSet::new().insert('é');
+    }
+
+    #[test]
+    fn distinct_window_finds_markers() {
+        assert_eq!(marker_end("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 4), Some(7));
+        assert_eq!(marker_end("bvwbjplbgvbhsrlpgdmjqwftvncz", 4), Some(5));
+        assert_eq!(marker_end("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 14), Some(19));
+        assert_eq!(marker_end("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 14), Some(26));
+        assert_eq!(marker_end("aaaa", 2), None);
+    }
+
+    #[test]
+    fn distinct_window_fills_up() {
+        let mut window = DistinctWindow::new(3);
+
+        window.push('a');
+        window.push('b');
+        assert!(!window.is_full());
+        assert!(!window.all_distinct());
+        assert_eq!(window.distinct(), 2);
+
+        window.push('a');
+        assert!(window.is_full());
+        assert_eq!(window.distinct(), 2);
+
+        window.push('c');
+        assert!(window.all_distinct());
+    }
+
+    proptest! {
+        #[test]
+        fn distinct_window_matches_a_hash_set(s in "[a-e]{0,40}", size in 1..8usize) {
+            let chars = s.chars().collect::<Vec<_>>();
+            let mut window = DistinctWindow::new(size);
+
+            for (i, &ch) in chars.iter().enumerate() {
+                window.push(ch);
+
+                let recent = &chars[(i + 1).saturating_sub(size)..=i];
+
+                prop_assert_eq!(window.distinct(), recent.iter().collect::<HashSet<_>>().len());
+                prop_assert_eq!(window.is_full(), recent.len() == size);
+            }
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! Cycle detection for deterministic simulations that eventually repeat themselves.
+//!
+//! The simulation is described by an initial state and a step closure. States are
+//! compared through a fingerprint, which is either the whole state or a projection of it,
+//! like the top rows of a tower. A projection must capture everything the future
+//! depends on, otherwise the detected cycle is only a coincidence.
+
+use num_traits::PrimInt;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Algorithm {
+    #[default]
+    Brent,
+    Floyd,
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct CycleDetector {
+    algorithm: Algorithm,
+    step_limit: Option<usize>,
+}
+
+/// A detected cycle, along with the metric after every step up to the end of its first period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<T> {
+    /// The number of steps before the first state that repeats
+    pub start: usize,
+    pub period: usize,
+    metrics: Vec<T>,
+}
+
+impl CycleDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Gives up once the search for a repetition has simulated this many steps
+    pub fn step_limit(mut self, steps: usize) -> Self {
+        self.step_limit = Some(steps);
+        self
+    }
+
+    /// Detects a cycle in the sequence of whole states.
+    ///
+    /// Returns `None` if the step limit is reached first.
+    /// Without a step limit, never returns if the sequence doesn't repeat.
+    pub fn detect<S, T>(
+        &self,
+        initial: &S,
+        step: impl FnMut(&mut S),
+        metric: impl FnMut(&S) -> T,
+    ) -> Option<Cycle<T>>
+    where
+        S: Clone + Eq,
+    {
+        self.detect_projected(initial, step, S::clone, metric)
+    }
+
+    /// Detects a cycle in the sequence of fingerprints, with the same limits as `detect`
+    pub fn detect_projected<S, F, T>(
+        &self,
+        initial: &S,
+        mut step: impl FnMut(&mut S),
+        mut fingerprint: impl FnMut(&S) -> F,
+        metric: impl FnMut(&S) -> T,
+    ) -> Option<Cycle<T>>
+    where
+        S: Clone,
+        F: Eq,
+    {
+        let step_limit = self.step_limit.unwrap_or(usize::MAX);
+        let period = match self.algorithm {
+            Algorithm::Brent => brent_period(initial, &mut step, &mut fingerprint, step_limit),
+            Algorithm::Floyd => floyd_period(initial, &mut step, &mut fingerprint, step_limit),
+        }?;
+
+        Some(find_start(initial, period, step, fingerprint, metric))
+    }
+}
+
+impl<T: PrimInt> Cycle<T> {
+    /// How much the metric changes over one period.
+    /// Panics if an unsigned metric decreases, as that can't be expressed in `T`.
+    pub fn metric_per_period(&self) -> T {
+        self.metrics[self.start + self.period]
+            .checked_sub(&self.metrics[self.start])
+            .expect("unsigned metric decreases over a period")
+    }
+
+    /// The metric after any number of steps, assuming it changes by the same amount every period.
+    /// The metric may grow or shrink.
+    pub fn extrapolate(&self, steps: usize) -> T {
+        if let Some(&metric) = self.metrics.get(steps) {
+            return metric;
+        }
+
+        let periods = (steps - self.start) / self.period;
+        let offset = (steps - self.start) % self.period;
+        let (first, last) = (self.metrics[self.start], self.metrics[self.start + self.period]);
+        let metric = self.metrics[self.start + offset];
+        // Growth and decline are handled separately, so unsigned metrics may decrease
+        let change = |per_period: T| {
+            T::from(periods)
+                .and_then(|periods| periods.checked_mul(&per_period))
+                .expect("extrapolated metric overflows")
+        };
+
+        if last >= first {
+            metric.checked_add(&change(last - first))
+        } else {
+            metric.checked_sub(&change(first - last))
+        }
+        .expect("extrapolated metric overflows")
+    }
+}
+
+impl<T> Cycle<T> {
+    /// The metric after `steps` steps, if they were simulated while detecting the cycle
+    pub fn metric(&self, steps: usize) -> Option<&T> {
+        self.metrics.get(steps)
+    }
+}
+
+/// Brent's algorithm: the hare searches ever longer powers of two ahead of a resting tortoise
+fn brent_period<S: Clone, F: Eq>(
+    initial: &S,
+    step: &mut impl FnMut(&mut S),
+    fingerprint: &mut impl FnMut(&S) -> F,
+    step_limit: usize,
+) -> Option<usize> {
+    let mut hare_state = initial.clone();
+    let mut tortoise = fingerprint(&hare_state);
+    let mut power = 1;
+    let mut period = 1;
+    let mut steps = 1;
+
+    step(&mut hare_state);
+
+    let mut hare = fingerprint(&hare_state);
+
+    while tortoise != hare {
+        if steps >= step_limit {
+            return None;
+        }
+
+        if power == period {
+            tortoise = hare;
+            power *= 2;
+            period = 0;
+        }
+
+        step(&mut hare_state);
+        hare = fingerprint(&hare_state);
+        period += 1;
+        steps += 1;
+    }
+
+    Some(period)
+}
+
+/// Floyd's algorithm: the hare moves twice as fast as the tortoise until they meet within the cycle
+fn floyd_period<S: Clone, F: Eq>(
+    initial: &S,
+    step: &mut impl FnMut(&mut S),
+    fingerprint: &mut impl FnMut(&S) -> F,
+    step_limit: usize,
+) -> Option<usize> {
+    let mut tortoise_state = initial.clone();
+    let mut hare_state = initial.clone();
+    let mut steps = 0usize;
+
+    loop {
+        // Each round takes a step with the tortoise and two with the hare
+        steps += 3;
+
+        if steps > step_limit {
+            return None;
+        }
+
+        step(&mut tortoise_state);
+        step(&mut hare_state);
+        step(&mut hare_state);
+
+        if fingerprint(&tortoise_state) == fingerprint(&hare_state) {
+            break;
+        }
+    }
+
+    let tortoise = fingerprint(&tortoise_state);
+    let mut period = 1;
+
+    step(&mut hare_state);
+
+    while fingerprint(&hare_state) != tortoise {
+        step(&mut hare_state);
+        period += 1;
+    }
+
+    Some(period)
+}
+
+/// Walks two states `period` steps apart until they meet, recording the metric of the leading one
+fn find_start<S: Clone, F: Eq, T>(
+    initial: &S,
+    period: usize,
+    mut step: impl FnMut(&mut S),
+    mut fingerprint: impl FnMut(&S) -> F,
+    mut metric: impl FnMut(&S) -> T,
+) -> Cycle<T> {
+    let mut tortoise_state = initial.clone();
+    let mut hare_state = initial.clone();
+    let mut metrics = vec![metric(&hare_state)];
+
+    for _ in 0..period {
+        step(&mut hare_state);
+        metrics.push(metric(&hare_state));
+    }
+
+    let mut start = 0;
+
+    while fingerprint(&tortoise_state) != fingerprint(&hare_state) {
+        step(&mut tortoise_state);
+        step(&mut hare_state);
+        metrics.push(metric(&hare_state));
+        start += 1;
+    }
+
+    Cycle {
+        start,
+        period,
+        metrics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALGORITHMS: [Algorithm; 2] = [Algorithm::Brent, Algorithm::Floyd];
+
+    fn next(x: u64) -> u64 {
+        (x * x + 1) % 255
+    }
+
+    /// The states visited by brute force, including the initial one
+    fn brute_force(initial: u64, steps: usize) -> Vec<u64> {
+        let mut states = vec![initial];
+
+        for _ in 0..steps {
+            states.push(next(*states.last().unwrap()));
+        }
+
+        states
+    }
+
+    #[test]
+    fn detects_the_first_repetition() {
+        for algorithm in ALGORITHMS {
+            for initial in 0..255 {
+                let cycle = CycleDetector::new()
+                    .algorithm(algorithm)
+                    .detect(&initial, |x| *x = next(*x), |&x| x)
+                    .unwrap();
+                let states = brute_force(initial, 600);
+                let first_repeat = (0..).find(|&i| states[..i].contains(&states[i])).unwrap();
+                let start = states.iter().position(|&x| x == states[first_repeat]).unwrap();
+
+                assert_eq!((cycle.start, cycle.period), (start, first_repeat - start), "{:?} from {}", algorithm, initial);
+            }
+        }
+    }
+
+    #[test]
+    fn extrapolates_before_at_and_after_the_start() {
+        for algorithm in ALGORITHMS {
+            // The running sum keeps growing, while the cycle is only in `x`
+            let cycle = CycleDetector::new().algorithm(algorithm).detect_projected(
+                &(3u64, 0u64),
+                |(x, sum)| {
+                    *x = next(*x);
+                    *sum += *x;
+                },
+                |&(x, _)| x,
+                |&(_, sum)| sum,
+            )
+            .unwrap();
+            let states = brute_force(3, 10_000);
+            let sums = states
+                .iter()
+                .scan(0, |sum, &x| {
+                    *sum += x;
+                    Some(*sum - states[0])
+                })
+                .collect::<Vec<_>>();
+
+            assert!(cycle.start > 0, "the example should have a tail");
+
+            for steps in (0..cycle.start + 3 * cycle.period).chain([cycle.start, 1234, 10_000]) {
+                assert_eq!(cycle.extrapolate(steps), sums[steps], "{:?} after {} steps", algorithm, steps);
+            }
+
+            assert_eq!(cycle.metric(cycle.start + cycle.period), Some(&sums[cycle.start + cycle.period]));
+            assert_eq!(cycle.metric(cycle.start + cycle.period + 1), None);
+        }
+    }
+
+    #[test]
+    fn whole_states_cycle_without_growth() {
+        for algorithm in ALGORITHMS {
+            let cycle = CycleDetector::new().algorithm(algorithm).detect(&3u64, |x| *x = next(*x), |&x| x).unwrap();
+            let states = brute_force(3, 5000);
+
+            assert_eq!(cycle.metric_per_period(), 0);
+            assert_eq!(cycle.extrapolate(5000), states[5000]);
+        }
+    }
+
+    #[test]
+    fn immediate_fixed_point() {
+        for algorithm in ALGORITHMS {
+            let cycle = CycleDetector::new().algorithm(algorithm).detect(&7, |_| {}, |&x: &u32| x).unwrap();
+
+            assert_eq!((cycle.start, cycle.period), (0, 1));
+            assert_eq!(cycle.extrapolate(100), 7);
+        }
+    }
+
+    #[test]
+    fn step_limit_gives_up() {
+        for algorithm in ALGORITHMS {
+            let detector = CycleDetector::new().algorithm(algorithm);
+            let mut steps = 0;
+            let counting = detector.step_limit(100).detect(
+                &0u64,
+                |x| {
+                    *x += 1;
+                    steps += 1;
+                },
+                |&x| x,
+            );
+
+            assert_eq!(counting, None);
+            assert!((98..=100).contains(&steps), "{:?} took {} steps", algorithm, steps);
+
+            // A limit that is high enough doesn't change the result
+            let limited = detector.step_limit(1000).detect(&3u64, |x| *x = next(*x), |&x| x);
+
+            assert_eq!(limited, detector.detect(&3u64, |x| *x = next(*x), |&x| x));
+            assert!(limited.is_some());
+        }
+    }
+
+    #[test]
+    fn decreasing_metrics() {
+        for algorithm in ALGORITHMS {
+            // Every period of 3 steps loses 6
+            let cycle = CycleDetector::new()
+                .algorithm(algorithm)
+                .detect_projected(
+                    &(0u8, 1000u32),
+                    |(phase, metric)| {
+                        *phase = (*phase + 1) % 3;
+                        *metric -= 2;
+                    },
+                    |&(phase, _)| phase,
+                    |&(_, metric)| metric,
+                )
+                .unwrap();
+
+            assert_eq!(cycle.extrapolate(100), 800);
+            assert_eq!(cycle.extrapolate(500), 0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "unsigned metric decreases over a period")]
+    fn unsigned_decline_per_period() {
+        let cycle = CycleDetector::new()
+            .detect_projected(
+                &(false, 10u32),
+                |(flip, metric)| {
+                    *flip = !*flip;
+                    *metric -= 1;
+                },
+                |&(flip, _)| flip,
+                |&(_, metric)| metric,
+            )
+            .unwrap();
+
+        cycle.metric_per_period();
+    }
+}
@@ -0,0 +1,307 @@
+use std::str::FromStr;
+use num_traits::{PrimInt, Signed};
+use crate::{geom::Vec2, ParseError};
+
+/// An orthogonal direction. `Up` points towards lower `y` values.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+/// An orthogonal or diagonal direction. `Up` points towards lower `y` values.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction4 {
+    /// All directions in clockwise order, starting with `Up`
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::ALL.iter().copied()
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Self::Up => (0, -1),
+            Self::Right => (1, 0),
+            Self::Down => (0, 1),
+            Self::Left => (-1, 0),
+        }
+    }
+
+    pub fn vector<T: PrimInt + Signed>(self) -> Vec2<T> {
+        offset_vector(self.offset())
+    }
+
+    /// Moves `pos` one step, unless that would leave the unsigned coordinate space
+    pub fn apply(self, pos: (usize, usize)) -> Option<(usize, usize)> {
+        apply_offset(pos, self.offset())
+    }
+
+    /// Moves `pos` one step, wrapping around to the opposite edge of a `width` by `height` grid.
+    /// Panics if the grid is empty.
+    pub fn apply_wrapping(self, pos: (usize, usize), width: usize, height: usize) -> (usize, usize) {
+        apply_offset_wrapping(pos, self.offset(), width, height)
+    }
+
+    /// Parses `U`/`D`/`L`/`R`, `N`/`S`/`E`/`W` and `^`/`v`/`<`/`>`
+    pub fn from_char(ch: char) -> Option<Self> {
+        Some(match ch {
+            'U' | 'N' | '^' => Self::Up,
+            'R' | 'E' | '>' => Self::Right,
+            'D' | 'S' | 'v' => Self::Down,
+            'L' | 'W' | '<' => Self::Left,
+            _ => return None,
+        })
+    }
+}
+
+impl Direction8 {
+    /// All directions in clockwise order, starting with `Up`
+    pub const ALL: [Self; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::ALL.iter().copied()
+    }
+
+    /// Turns by 45° counterclockwise
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    /// Turns by 45° clockwise
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Self::Up => (0, -1),
+            Self::UpRight => (1, -1),
+            Self::Right => (1, 0),
+            Self::DownRight => (1, 1),
+            Self::Down => (0, 1),
+            Self::DownLeft => (-1, 1),
+            Self::Left => (-1, 0),
+            Self::UpLeft => (-1, -1),
+        }
+    }
+
+    pub fn vector<T: PrimInt + Signed>(self) -> Vec2<T> {
+        offset_vector(self.offset())
+    }
+
+    /// Moves `pos` one step, unless that would leave the unsigned coordinate space
+    pub fn apply(self, pos: (usize, usize)) -> Option<(usize, usize)> {
+        apply_offset(pos, self.offset())
+    }
+
+    /// Moves `pos` one step, wrapping around to the opposite edge of a `width` by `height` grid.
+    /// Panics if the grid is empty.
+    pub fn apply_wrapping(self, pos: (usize, usize), width: usize, height: usize) -> (usize, usize) {
+        apply_offset_wrapping(pos, self.offset(), width, height)
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(direction: Direction4) -> Self {
+        match direction {
+            Direction4::Up => Self::Up,
+            Direction4::Right => Self::Right,
+            Direction4::Down => Self::Down,
+            Direction4::Left => Self::Left,
+        }
+    }
+}
+
+impl FromStr for Direction4 {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let mut chars = s.chars();
+
+        match (chars.next().and_then(Self::from_char), chars.next()) {
+            (Some(direction), None) => Ok(direction),
+            _ => Err(ParseError::new(s, "one of U/D/L/R, N/S/E/W or ^/v/</>")),
+        }
+    }
+}
+
+/// Parses `N`, `NE`, `E`, `SE`, `S`, `SW`, `W`, `NW` as well as
+/// the orthogonal directions accepted by `Direction4`
+impl FromStr for Direction8 {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        Ok(match s {
+            "NE" => Self::UpRight,
+            "SE" => Self::DownRight,
+            "SW" => Self::DownLeft,
+            "NW" => Self::UpLeft,
+            _ => s.parse::<Direction4>()
+                .map_err(|_| ParseError::new(s, "a compass direction like N or NE"))?
+                .into(),
+        })
+    }
+}
+
+fn offset_vector<T: PrimInt + Signed>((dx, dy): (isize, isize)) -> Vec2<T> {
+    Vec2::new(T::from(dx).unwrap(), T::from(dy).unwrap())
+}
+
+fn apply_offset((x, y): (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+    Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?))
+}
+
+fn apply_offset_wrapping(
+    (x, y): (usize, usize),
+    (dx, dy): (isize, isize),
+    width: usize,
+    height: usize,
+) -> (usize, usize) {
+    assert!(width > 0 && height > 0, "can't wrap around an empty {}x{} grid", width, height);
+
+    let wrap = |value: usize, delta: isize, len: usize| {
+        (value as isize + delta).rem_euclid(len as isize) as usize
+    };
+
+    (wrap(x, dx, width), wrap(y, dy, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns_4() {
+        use Direction4::*;
+
+        assert_eq!(Up.turn_left(), Left);
+        assert_eq!(Left.turn_right(), Up);
+        assert_eq!(Right.turn_right(), Down);
+        assert_eq!(Down.opposite(), Up);
+        assert_eq!(Left.opposite(), Right);
+
+        for direction in Direction4::iter() {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(direction.opposite().vector::<i32>(), -direction.vector());
+        }
+    }
+
+    #[test]
+    fn turns_8() {
+        use Direction8::*;
+
+        assert_eq!(Up.turn_left(), UpLeft);
+        assert_eq!(UpLeft.turn_right(), Up);
+        assert_eq!(DownRight.turn_right(), Down);
+        assert_eq!(UpRight.opposite(), DownLeft);
+        assert_eq!(Left.opposite(), Right);
+
+        for direction in Direction8::iter() {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!((0..4).fold(direction, |direction, _| direction.turn_right()), direction.opposite());
+            assert_eq!(direction.opposite().vector::<i32>(), -direction.vector());
+        }
+    }
+
+    #[test]
+    fn parse_4() {
+        let alphabets = ["URDL", "NESW", "^>v<"];
+
+        for alphabet in alphabets {
+            let parsed = alphabet.chars().map(|ch| ch.to_string().parse::<Direction4>().unwrap());
+
+            assert!(parsed.eq(Direction4::iter()), "{}", alphabet);
+        }
+
+        for invalid in ["", "u", "UD", "X", "V", "NE"] {
+            assert!(invalid.parse::<Direction4>().is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn parse_8() {
+        let names = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+
+        assert!(names.iter().map(|name| name.parse::<Direction8>().unwrap()).eq(Direction8::iter()));
+        assert_eq!("^".parse::<Direction8>().unwrap(), Direction8::Up);
+        assert_eq!("L".parse::<Direction8>().unwrap(), Direction8::Left);
+
+        for invalid in ["", "EN", "NEE", "ne", "UR", "N E"] {
+            assert!(invalid.parse::<Direction8>().is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn apply_at_zero() {
+        assert_eq!(Direction4::Up.apply((3, 0)), None);
+        assert_eq!(Direction4::Left.apply((0, 3)), None);
+        assert_eq!(Direction4::Down.apply((0, 0)), Some((0, 1)));
+        assert_eq!(Direction8::UpRight.apply((0, 0)), None);
+        assert_eq!(Direction8::DownLeft.apply((0, 5)), None);
+        assert_eq!(Direction8::DownRight.apply((0, 0)), Some((1, 1)));
+    }
+
+    #[test]
+    fn apply_wrapping_at_the_edges() {
+        assert_eq!(Direction4::Up.apply_wrapping((2, 0), 4, 3), (2, 2));
+        assert_eq!(Direction4::Down.apply_wrapping((2, 2), 4, 3), (2, 0));
+        assert_eq!(Direction4::Left.apply_wrapping((0, 1), 4, 3), (3, 1));
+        assert_eq!(Direction4::Right.apply_wrapping((3, 1), 4, 3), (0, 1));
+        assert_eq!(Direction4::Right.apply_wrapping((1, 1), 4, 3), (2, 1));
+        assert_eq!(Direction8::UpLeft.apply_wrapping((0, 0), 4, 3), (3, 2));
+        assert_eq!(Direction8::DownRight.apply_wrapping((3, 2), 4, 3), (0, 0));
+        assert_eq!(Direction8::Up.apply_wrapping((0, 0), 1, 1), (0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "can't wrap around an empty 0x3 grid")]
+    fn apply_wrapping_on_an_empty_grid() {
+        Direction4::Right.apply_wrapping((0, 0), 0, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "can't wrap around an empty 4x0 grid")]
+    fn apply_wrapping_8_on_an_empty_grid() {
+        Direction8::Up.apply_wrapping((0, 0), 4, 0);
+    }
+}
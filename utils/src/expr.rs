@@ -0,0 +1,542 @@
+//! Named arithmetic expressions that refer to each other, like `root: pppw + sjmn`.
+//!
+//! Every name is evaluated at most once per evaluation, so shared subexpressions are cheap.
+
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    convert::TryFrom,
+    error::Error,
+    fmt,
+    iter::FromIterator,
+    str::FromStr,
+};
+use crate::ParseError;
+
+/// Unknowns are searched for within `-SEARCH_BOUND..=SEARCH_BOUND` when they can't be solved for directly
+pub const SEARCH_BOUND: i64 = 1 << 53;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Job {
+    Number(i64),
+    Op { lhs: String, op: Op, rhs: String },
+}
+
+/// Numbers that expressions can be evaluated with
+pub trait Number: Copy + Ord + fmt::Debug {
+    fn from_i64(value: i64) -> Self;
+
+    /// Applies `op`, returning `None` on overflow or division by zero
+    fn apply(op: Op, lhs: Self, rhs: Self) -> Option<Self>;
+}
+
+/// Checked arithmetic. Division truncates towards zero.
+impl Number for i64 {
+    fn from_i64(value: i64) -> Self {
+        value
+    }
+
+    fn apply(op: Op, lhs: Self, rhs: Self) -> Option<Self> {
+        match op {
+            Op::Add => lhs.checked_add(rhs),
+            Op::Sub => lhs.checked_sub(rhs),
+            Op::Mul => lhs.checked_mul(rhs),
+            Op::Div => lhs.checked_div(rhs),
+        }
+    }
+}
+
+/// An exact fraction, always stored in lowest terms with a positive denominator
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    /// Returns `None` if the denominator is zero
+    pub fn new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+
+        let divisor = gcd(numerator.unsigned_abs(), denominator.unsigned_abs()) as i128;
+        let sign = denominator.signum();
+
+        Some(Self {
+            numerator: sign * numerator / divisor,
+            denominator: sign * denominator / divisor,
+        })
+    }
+
+    pub fn numerator(self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> i128 {
+        self.denominator
+    }
+
+    /// The value as an `i64`, if it is a whole number that fits
+    pub fn to_i64(self) -> Option<i64> {
+        if self.denominator != 1 {
+            return None;
+        }
+
+        i64::try_from(self.numerator).ok()
+    }
+}
+
+impl Number for Rational {
+    fn from_i64(value: i64) -> Self {
+        Self {
+            numerator: value.into(),
+            denominator: 1,
+        }
+    }
+
+    fn apply(op: Op, lhs: Self, rhs: Self) -> Option<Self> {
+        let (a, b, c, d) = (lhs.numerator, lhs.denominator, rhs.numerator, rhs.denominator);
+
+        match op {
+            Op::Add => Self::new(a.checked_mul(d)?.checked_add(c.checked_mul(b)?)?, b.checked_mul(d)?),
+            Op::Sub => Self::new(a.checked_mul(d)?.checked_sub(c.checked_mul(b)?)?, b.checked_mul(d)?),
+            Op::Mul => Self::new(a.checked_mul(c)?, b.checked_mul(d)?),
+            Op::Div => Self::new(a.checked_mul(d)?, b.checked_mul(c)?),
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_fractions(self.numerator, self.denominator, other.numerator, other.denominator)
+    }
+}
+
+/// Compares `a / b` with `c / d` for positive denominators without multiplying them, which could overflow.
+/// Equal whole parts leave the remainders to compare, whose reciprocals compare the other way round.
+fn compare_fractions(a: i128, b: i128, c: i128, d: i128) -> Ordering {
+    let (whole_a, whole_c) = (a.div_euclid(b), c.div_euclid(d));
+
+    if whole_a != whole_c {
+        return whole_a.cmp(&whole_c);
+    }
+
+    let (rem_a, rem_c) = (a.rem_euclid(b), c.rem_euclid(d));
+
+    match (rem_a, rem_c) {
+        (0, 0) => Ordering::Equal,
+        (0, _) => Ordering::Less,
+        (_, 0) => Ordering::Greater,
+        _ => compare_fractions(d, rem_c, b, rem_a),
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprError {
+    UnknownName(String),
+    /// Evaluating the named expression overflowed or divided by zero
+    Arithmetic(String),
+    /// The named expression refers to itself
+    Cycle(String),
+    /// The unknown appears on both sides of the equation, or on neither
+    NotSolvable,
+    NoSolution,
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownName(name) => write!(f, "unknown name {:?}", name),
+            Self::Arithmetic(name) => write!(f, "overflow or division by zero in {:?}", name),
+            Self::Cycle(name) => write!(f, "{:?} depends on itself", name),
+            Self::NotSolvable => write!(f, "the unknown must appear on exactly one side"),
+            Self::NoSolution => write!(f, "no value of the unknown satisfies the equation"),
+        }
+    }
+}
+
+impl Error for ExprError {}
+
+#[derive(Debug, Default, Clone)]
+pub struct Expressions {
+    jobs: HashMap<String, Job>,
+}
+
+impl Expressions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, job: Job) {
+        self.jobs.insert(name.into(), job);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Job> {
+        self.jobs.get(name)
+    }
+
+    pub fn eval<N: Number>(&self, name: &str) -> Result<N, ExprError> {
+        self.eval_with(name, None)
+    }
+
+    /// Evaluates `name`, treating `unknown` as the given value instead of its own job
+    pub fn eval_with<N: Number>(&self, name: &str, unknown: Option<(&str, N)>) -> Result<N, ExprError> {
+        let mut memo = HashMap::new();
+
+        if let Some((unknown, value)) = unknown {
+            memo.insert(unknown, Some(value));
+        }
+
+        self.eval_memo(name, &mut memo)
+    }
+
+    /// Finds the value of `unknown` that makes `lhs` and `rhs` evaluate to the same value.
+    ///
+    /// Operations are inverted along the path from the equation to the unknown. Should an operation
+    /// not be exactly invertible, like a truncating division, the unknown is binary searched instead,
+    /// which assumes that the side containing it is monotonic in it.
+    pub fn solve<N: Number>(&self, unknown: &str, lhs: &str, rhs: &str) -> Result<N, ExprError> {
+        let mut dependents = HashMap::new();
+        let lhs_depends = self.depends_on(lhs, unknown, &mut dependents)?;
+        let rhs_depends = self.depends_on(rhs, unknown, &mut dependents)?;
+
+        let (dependent, target) = match (lhs_depends, rhs_depends) {
+            (true, false) => (lhs, self.eval(rhs)?),
+            (false, true) => (rhs, self.eval(lhs)?),
+            _ => return Err(ExprError::NotSolvable),
+        };
+
+        match self.invert(dependent, target, unknown, &dependents)? {
+            Some(value) => Ok(value),
+            None => self.binary_search(dependent, target, unknown),
+        }
+    }
+
+    fn eval_memo<'a, N: Number>(&'a self, name: &'a str, memo: &mut HashMap<&'a str, Option<N>>) -> Result<N, ExprError> {
+        // An entry without a value is currently being evaluated further up
+        match memo.get(name) {
+            Some(Some(value)) => return Ok(*value),
+            Some(None) => return Err(ExprError::Cycle(name.into())),
+            None => {}
+        }
+
+        memo.insert(name, None);
+
+        let value = match self.job(name)? {
+            Job::Number(value) => N::from_i64(*value),
+            Job::Op { lhs, op, rhs } => {
+                let lhs = self.eval_memo(lhs, memo)?;
+                let rhs = self.eval_memo(rhs, memo)?;
+
+                N::apply(*op, lhs, rhs).ok_or_else(|| ExprError::Arithmetic(name.into()))?
+            }
+        };
+
+        memo.insert(name, Some(value));
+
+        Ok(value)
+    }
+
+    fn depends_on<'a>(&'a self, name: &'a str, unknown: &str, memo: &mut HashMap<&'a str, bool>) -> Result<bool, ExprError> {
+        if name == unknown {
+            return Ok(true);
+        }
+
+        if let Some(&depends) = memo.get(name) {
+            return Ok(depends);
+        }
+
+        let depends = match self.job(name)? {
+            Job::Number(_) => false,
+            Job::Op { lhs, rhs, .. } => {
+                self.depends_on(lhs, unknown, memo)? | self.depends_on(rhs, unknown, memo)?
+            }
+        };
+
+        memo.insert(name, depends);
+
+        Ok(depends)
+    }
+
+    /// Walks from `name` down to the unknown, undoing every operation on the way.
+    /// Returns `None` if an operation can't be undone exactly.
+    fn invert<'a, N: Number>(
+        &'a self,
+        mut name: &'a str,
+        mut target: N,
+        unknown: &str,
+        dependents: &HashMap<&str, bool>,
+    ) -> Result<Option<N>, ExprError> {
+        while name != unknown {
+            let Job::Op { lhs, op, rhs } = self.job(name)? else {
+                unreachable!("numbers don't depend on the unknown");
+            };
+
+            let op = *op;
+            let depends = |name: &str| name == unknown || dependents.get(name) == Some(&true);
+            let unknown_on_left = depends(lhs);
+
+            if unknown_on_left && depends(rhs) {
+                return Ok(None);
+            }
+
+            let candidate = if unknown_on_left {
+                let known = self.eval(rhs)?;
+                let inverse = match op {
+                    Op::Add => Op::Sub,
+                    Op::Sub => Op::Add,
+                    Op::Mul => Op::Div,
+                    Op::Div => Op::Mul,
+                };
+
+                N::apply(inverse, target, known).filter(|&candidate| N::apply(op, candidate, known) == Some(target))
+            } else {
+                let known = self.eval(lhs)?;
+                let candidate = match op {
+                    Op::Add => N::apply(Op::Sub, target, known),
+                    Op::Sub => N::apply(Op::Sub, known, target),
+                    Op::Mul => N::apply(Op::Div, target, known),
+                    Op::Div => N::apply(Op::Div, known, target),
+                };
+
+                candidate.filter(|&candidate| N::apply(op, known, candidate) == Some(target))
+            };
+
+            let Some(candidate) = candidate else {
+                return Ok(None);
+            };
+
+            target = candidate;
+            name = if unknown_on_left { lhs } else { rhs };
+        }
+
+        Ok(Some(target))
+    }
+
+    /// Finds the smallest value of the unknown for which `name` evaluates to `target`.
+    ///
+    /// Values at which the evaluation overflows are taken to be out of range, so the search
+    /// bounds are halved towards zero until the expression can be evaluated at them.
+    fn binary_search<N: Number>(&self, name: &str, target: N, unknown: &str) -> Result<N, ExprError> {
+        // `None` if the evaluation overflows or divides by zero
+        let compare = |value: i64| -> Result<Option<Ordering>, ExprError> {
+            match self.eval_with(name, Some((unknown, N::from_i64(value)))) {
+                Ok(value) => Ok(Some(value.cmp(&target))),
+                Err(ExprError::Arithmetic(_)) => Ok(None),
+                Err(error) => Err(error),
+            }
+        };
+        let shrink = |mut bound: i64| -> Result<Option<(i64, Ordering)>, ExprError> {
+            loop {
+                if let Some(ordering) = compare(bound)? {
+                    return Ok(Some((bound, ordering)));
+                }
+
+                if bound == 0 {
+                    return Ok(None);
+                }
+
+                bound /= 2;
+            }
+        };
+
+        let ((mut low, low_ordering), (mut high, high_ordering)) = match (shrink(-SEARCH_BOUND)?, shrink(SEARCH_BOUND)?) {
+            (Some(low), Some(high)) => (low, high),
+            _ => return Err(ExprError::NoSolution),
+        };
+
+        if low_ordering == Ordering::Equal {
+            return Ok(N::from_i64(low));
+        }
+
+        if high_ordering == low_ordering {
+            return Err(ExprError::NoSolution);
+        }
+
+        // The comparison at `low` is always `low_ordering`, and at `high` it never is
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+
+            match compare(mid)? {
+                Some(ordering) if ordering == low_ordering => low = mid,
+                Some(_) => high = mid,
+                None => return Err(ExprError::Arithmetic(name.into())),
+            }
+        }
+
+        match compare(high)? {
+            Some(Ordering::Equal) => Ok(N::from_i64(high)),
+            _ => Err(ExprError::NoSolution),
+        }
+    }
+
+    fn job(&self, name: &str) -> Result<&Job, ExprError> {
+        self.jobs.get(name).ok_or_else(|| ExprError::UnknownName(name.into()))
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let rem = a % b;
+        a = b;
+        b = rem;
+    }
+
+    a
+}
+
+impl<S: Into<String>> FromIterator<(S, Job)> for Expressions {
+    fn from_iter<I: IntoIterator<Item = (S, Job)>>(iter: I) -> Self {
+        Self {
+            jobs: iter.into_iter().map(|(name, job)| (name.into(), job)).collect(),
+        }
+    }
+}
+
+impl FromStr for Op {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        Ok(match s {
+            "+" => Self::Add,
+            "-" => Self::Sub,
+            "*" => Self::Mul,
+            "/" => Self::Div,
+            _ => return Err(ParseError::new(s, "one of + - * /")),
+        })
+    }
+}
+
+/// Parses either an integer or an operation on two names, like `pppw + sjmn`
+impl FromStr for Job {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        if let Ok(value) = s.trim().parse::<i64>() {
+            return Ok(Self::Number(value));
+        }
+
+        let mut parts = s.split_whitespace();
+
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(lhs), Some(op), Some(rhs), None) => Ok(Self::Op {
+                lhs: lhs.into(),
+                op: op.parse()?,
+                rhs: rhs.into(),
+            }),
+            _ => Err(ParseError::new(s, "a number or an operation like `a + b`")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32";
+
+    fn parse(input: &str) -> Expressions {
+        input
+            .lines()
+            .map(|line| {
+                let (name, job) = line.split_once(": ").unwrap();
+
+                (name, job.parse::<Job>().unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn example() {
+        let monkeys = parse(EXAMPLE);
+
+        assert_eq!(monkeys.eval::<i64>("root"), Ok(152));
+        assert_eq!(monkeys.solve::<Rational>("humn", "pppw", "sjmn").unwrap().to_i64(), Some(301));
+        assert_eq!(monkeys.solve::<i64>("humn", "pppw", "sjmn"), Ok(301));
+    }
+
+    #[test]
+    fn binary_search_skips_overflowing_bounds() {
+        let monkeys = parse("a: b * c\nb: d / e\nc: 4096\nd: humn * f\ne: 3\nf: 2\ng: 4100096\nhumn: 0");
+
+        assert_eq!(monkeys.solve::<i64>("humn", "a", "g"), Ok(1502));
+    }
+
+    #[test]
+    fn unknown_on_both_sides_is_searched() {
+        // humn * humn * humn can't be inverted, but it is monotonic
+        let monkeys = parse("root: a + b\na: humn * s\ns: humn * humn\nb: 6\nc: 33\nd: 34\nhumn: 0");
+
+        assert_eq!(monkeys.solve::<i64>("humn", "root", "c"), Ok(3));
+        assert_eq!(monkeys.solve::<i64>("humn", "root", "d"), Err(ExprError::NoSolution));
+        assert_eq!(monkeys.solve::<i64>("humn", "root", "humn"), Err(ExprError::NotSolvable));
+        assert_eq!(monkeys.solve::<i64>("humn", "b", "c"), Err(ExprError::NotSolvable));
+    }
+
+    #[test]
+    fn errors() {
+        let monkeys = parse("a: b / c\nb: 1\nc: 0\nd: d + b\ne: x + b");
+
+        assert_eq!(monkeys.eval::<i64>("a"), Err(ExprError::Arithmetic("a".into())));
+        assert_eq!(monkeys.eval::<i64>("d"), Err(ExprError::Cycle("d".into())));
+        assert_eq!(monkeys.eval::<i64>("e"), Err(ExprError::UnknownName("x".into())));
+        assert!("a % b".parse::<Job>().is_err());
+        assert!("a +".parse::<Job>().is_err());
+    }
+
+    #[test]
+    fn rational_ordering_does_not_overflow() {
+        let big = Rational::new(i128::MAX, 3).unwrap();
+        let bigger = Rational::new(i128::MAX - 1, 2).unwrap();
+        let tiny = Rational::new(1, i128::MAX).unwrap();
+        let tinier = Rational::new(1, i128::MAX - 1).unwrap();
+
+        assert_eq!(big.cmp(&bigger), Ordering::Less);
+        assert_eq!(bigger.cmp(&big), Ordering::Greater);
+        assert_eq!(tinier.cmp(&tiny), Ordering::Greater);
+        assert_eq!(Rational::new(-1, 2).unwrap().cmp(&Rational::new(1, -3).unwrap()), Ordering::Less);
+        assert_eq!(Rational::new(4, 6), Rational::new(-2, -3));
+        assert_eq!(Rational::new(4, 6).unwrap().to_string(), "2/3");
+        assert_eq!(Rational::new(1, 0), None);
+    }
+}
@@ -0,0 +1,330 @@
+use std::{
+    convert::TryFrom,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+use num_traits::{PrimInt, Signed};
+
+/// A 2D vector. `y` grows downwards, like the rows of a puzzle input.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    /// Converts the components, failing if any of them doesn't fit into `U`
+    pub fn try_cast<U: TryFrom<T>>(self) -> Option<Vec2<U>> {
+        Some(Vec2 {
+            x: U::try_from(self.x).ok()?,
+            y: U::try_from(self.y).ok()?,
+        })
+    }
+}
+
+impl<T: PrimInt> Vec2<T> {
+    /// Panics if a coordinate difference doesn't fit into `T`
+    pub fn manhattan_distance(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    /// Panics if a coordinate difference doesn't fit into `T`
+    pub fn chebyshev_distance(self, other: Self) -> T {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+
+    /// The smaller of each pair of components
+    pub fn component_min(self, other: Self) -> Self {
+        Self::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    /// The larger of each pair of components
+    pub fn component_max(self, other: Self) -> Self {
+        Self::new(self.x.max(other.x), self.y.max(other.y))
+    }
+}
+
+impl<T: PrimInt + Signed> Vec2<T> {
+    pub fn manhattan(self) -> T {
+        self.x.abs() + self.y.abs()
+    }
+
+    pub fn chebyshev(self) -> T {
+        self.x.abs().max(self.y.abs())
+    }
+
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn abs(self) -> Self {
+        Self::new(self.x.abs(), self.y.abs())
+    }
+
+    /// Rotates by 90° counterclockwise, as seen on screen
+    pub fn rotate_left(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    /// Rotates by 90° clockwise, as seen on screen
+    pub fn rotate_right(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+}
+
+impl<T> Vec3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Converts the components, failing if any of them doesn't fit into `U`
+    pub fn try_cast<U: TryFrom<T>>(self) -> Option<Vec3<U>> {
+        Some(Vec3 {
+            x: U::try_from(self.x).ok()?,
+            y: U::try_from(self.y).ok()?,
+            z: U::try_from(self.z).ok()?,
+        })
+    }
+}
+
+impl<T: PrimInt> Vec3<T> {
+    /// Panics if a coordinate difference doesn't fit into `T`
+    pub fn manhattan_distance(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y) + abs_diff(self.z, other.z)
+    }
+
+    /// Panics if a coordinate difference doesn't fit into `T`
+    pub fn chebyshev_distance(self, other: Self) -> T {
+        abs_diff(self.x, other.x)
+            .max(abs_diff(self.y, other.y))
+            .max(abs_diff(self.z, other.z))
+    }
+
+    /// The smaller of each pair of components
+    pub fn component_min(self, other: Self) -> Self {
+        Self::new(self.x.min(other.x), self.y.min(other.y), self.z.min(other.z))
+    }
+
+    /// The larger of each pair of components
+    pub fn component_max(self, other: Self) -> Self {
+        Self::new(self.x.max(other.x), self.y.max(other.y), self.z.max(other.z))
+    }
+}
+
+impl<T: PrimInt + Signed> Vec3<T> {
+    pub fn manhattan(self) -> T {
+        self.x.abs() + self.y.abs() + self.z.abs()
+    }
+
+    pub fn chebyshev(self) -> T {
+        self.x.abs().max(self.y.abs()).max(self.z.abs())
+    }
+
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+
+    pub fn abs(self) -> Self {
+        Self::new(self.x.abs(), self.y.abs(), self.z.abs())
+    }
+
+    /// Rotates by 90° around the x axis, counterclockwise when looking towards the origin
+    pub fn rotate_x(self) -> Self {
+        Self::new(self.x, -self.z, self.y)
+    }
+
+    /// Rotates by 90° around the y axis, counterclockwise when looking towards the origin
+    pub fn rotate_y(self) -> Self {
+        Self::new(self.z, self.y, -self.x)
+    }
+
+    /// Rotates by 90° around the z axis, counterclockwise when looking towards the origin
+    pub fn rotate_z(self) -> Self {
+        Self::new(-self.y, self.x, self.z)
+    }
+}
+
+/// Panics if the difference doesn't fit into `T`, e.g. `i32::MAX` and `i32::MIN`
+fn abs_diff<T: PrimInt>(a: T, b: T) -> T {
+    let (hi, lo) = if a > b { (a, b) } else { (b, a) };
+
+    hi.checked_sub(&lo).expect("coordinate difference overflows")
+}
+
+macro_rules! impl_ops {
+    ($vec:ident { $($field:ident),* }) => {
+        impl<T: Add<Output = T>> Add for $vec<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($field: self.$field + rhs.$field),* }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $vec<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$field += rhs.$field;)*
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $vec<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($field: self.$field - rhs.$field),* }
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $vec<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$field -= rhs.$field;)*
+            }
+        }
+
+        impl<T: Mul<Output = T> + Copy> Mul<T> for $vec<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                Self { $($field: self.$field * rhs),* }
+            }
+        }
+
+        impl<T: MulAssign + Copy> MulAssign<T> for $vec<T> {
+            fn mul_assign(&mut self, rhs: T) {
+                $(self.$field *= rhs;)*
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $vec<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),* }
+            }
+        }
+    };
+}
+
+impl_ops!(Vec2 { x, y });
+impl_ops!(Vec3 { x, y, z });
+
+impl<T> From<(T, T)> for Vec2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<Vec2<T>> for (T, T) {
+    fn from(vec: Vec2<T>) -> Self {
+        (vec.x, vec.y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Vec3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T> From<Vec3<T>> for (T, T, T) {
+    fn from(vec: Vec3<T>) -> Self {
+        (vec.x, vec.y, vec.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn component_min_and_max() {
+        let a = Vec2::new(1, 5);
+        let b = Vec2::new(3, -2);
+
+        assert_eq!(a.component_min(b), Vec2::new(1, -2));
+        assert_eq!(a.component_max(b), Vec2::new(3, 5));
+        assert_eq!(Ord::min(a, b), a);
+
+        let a = Vec3::new(1, 5, 0);
+        let b = Vec3::new(3, -2, 0);
+
+        assert_eq!(a.component_min(b), Vec3::new(1, -2, 0));
+        assert_eq!(a.component_max(b), Vec3::new(3, 5, 0));
+    }
+
+    #[test]
+    fn rotations_in_2d() {
+        // Up on screen is -y, so turning left from up faces left
+        let up = Vec2::new(0, -1);
+
+        assert_eq!(up.rotate_left(), Vec2::new(-1, 0));
+        assert_eq!(up.rotate_right(), Vec2::new(1, 0));
+        assert_eq!(up.rotate_left().rotate_right(), up);
+        assert_eq!(up.rotate_right().rotate_right(), -up);
+
+        let v = Vec2::new(2, 7);
+        let full_turn = (0..4).fold(v, |v, _| v.rotate_left());
+
+        assert_eq!(full_turn, v);
+        assert_eq!(v.rotate_left().manhattan(), v.manhattan());
+    }
+
+    #[test]
+    fn rotations_in_3d() {
+        let x = Vec3::new(1, 0, 0);
+        let y = Vec3::new(0, 1, 0);
+        let z = Vec3::new(0, 0, 1);
+
+        assert_eq!(y.rotate_x(), z);
+        assert_eq!(z.rotate_y(), x);
+        assert_eq!(x.rotate_z(), y);
+        assert_eq!(x.rotate_x(), x);
+
+        let v = Vec3::new(1, 2, 3);
+
+        for rotate in [Vec3::rotate_x, Vec3::rotate_y, Vec3::rotate_z] {
+            let full_turn = (0..4).fold(v, |v, _| rotate(v));
+
+            assert_eq!(full_turn, v);
+            assert_ne!(rotate(v), v);
+        }
+    }
+
+    #[test]
+    fn try_cast() {
+        assert_eq!(Vec2::new(3i64, 4).try_cast::<usize>(), Some(Vec2::new(3, 4)));
+        assert_eq!(Vec2::new(3i64, -4).try_cast::<usize>(), None);
+        assert_eq!(Vec2::new(300i32, 0).try_cast::<u8>(), None);
+        assert_eq!(Vec3::new(1usize, 2, 3).try_cast::<i8>(), Some(Vec3::new(1, 2, 3)));
+        assert_eq!(Vec3::new(1i64, 2, i64::MIN).try_cast::<i32>(), None);
+    }
+
+    #[test]
+    fn distances() {
+        let a = Vec2::new(1u32, 8);
+        let b = Vec2::new(4, 2);
+
+        assert_eq!(a.manhattan_distance(b), 9);
+        assert_eq!(a.chebyshev_distance(b), 6);
+        assert_eq!(Vec3::new(-1, 0, 2).manhattan_distance(Vec3::new(1, 1, -1)), 6);
+        assert_eq!(Vec2::new(i32::MIN, 0).chebyshev_distance(Vec2::new(-1, 0)), i32::MAX);
+        assert_eq!(Vec2::new(u8::MAX, 0).chebyshev_distance(Vec2::new(0, 0)), u8::MAX);
+        assert_eq!(Vec3::new(-1, 0, 2).chebyshev(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "coordinate difference overflows")]
+    fn distance_overflow() {
+        let _ = Vec2::new(i32::MIN, 0).manhattan_distance(Vec2::new(i32::MAX, 0));
+    }
+}
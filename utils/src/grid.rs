@@ -0,0 +1,343 @@
+use std::{
+    iter,
+    ops::{Index, IndexMut},
+};
+use crate::geom::Vec2;
+
+mod sparse;
+pub use sparse::SparseGrid;
+
+const OFFSETS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const OFFSETS_8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// A dense, row-major grid addressed by `(x, y)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert!(width > 0 || cells.is_empty(), "grid of width 0 can't hold cells");
+
+        let height = cells.len().checked_div(width).unwrap_or(0);
+
+        assert_eq!(width * height, cells.len(), "cells don't fill the last row");
+
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Parses a character map, one row per line
+    pub fn parse(s: &str, mut f: impl FnMut(char) -> T) -> Self {
+        let mut cells = Vec::with_capacity(s.len());
+        let mut width = None;
+
+        for line in s.lines() {
+            let len_before = cells.len();
+            cells.extend(line.chars().map(&mut f));
+            let line_width = cells.len() - len_before;
+
+            match width {
+                None => width = Some(line_width),
+                Some(width) => assert_eq!(width, line_width, "rows differ in width: {:?}", line),
+            }
+        }
+
+        Self::from_vec(width.unwrap_or(0), cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> Option<&T> {
+        let index = self.index_of(pos)?;
+
+        Some(&self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, pos: (usize, usize)) -> Option<&mut T> {
+        let index = self.index_of(pos)?;
+
+        Some(&mut self.cells[index])
+    }
+
+    pub fn set(&mut self, pos: (usize, usize), value: T) {
+        self[pos] = value;
+    }
+
+    /// All positions in row-major order
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn position(&self, f: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        let index = self.cells.iter().position(f)?;
+
+        Some((index % self.width, index / self.width))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} out of bounds", x);
+
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Moves `pos` by `offset`, unless that leaves the grid
+    pub fn offset(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+        let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+
+        self.contains(pos).then_some(pos)
+    }
+
+    /// The orthogonally adjacent positions within the grid
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        OFFSETS_4.iter().filter_map(move |&offset| self.offset(pos, offset))
+    }
+
+    /// The orthogonally and diagonally adjacent positions within the grid
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        OFFSETS_8.iter().filter_map(move |&offset| self.offset(pos, offset))
+    }
+
+    /// The positions reached by repeatedly stepping by `step` from `pos`, excluding `pos` itself.
+    /// Panics if `step` is `(0, 0)`, which would never leave the grid.
+    pub fn ray_positions(
+        &self,
+        mut pos: (usize, usize),
+        step: (isize, isize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        assert!(step != (0, 0), "a ray needs a non-zero step");
+
+        iter::from_fn(move || {
+            pos = self.offset(pos, step)?;
+
+            Some(pos)
+        })
+    }
+
+    /// The cells reached by repeatedly stepping by `step` from `pos`, excluding `pos` itself.
+    /// Panics if `step` is `(0, 0)`.
+    pub fn ray(&self, pos: (usize, usize), step: (isize, isize)) -> impl Iterator<Item = &T> {
+        self.ray_positions(pos, step).map(move |pos| &self[pos])
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn index_of(&self, (x, y): (usize, usize)) -> Option<usize> {
+        self.contains((x, y)).then(|| y * self.width + x)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is out of bounds", pos))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is out of bounds", pos))
+    }
+}
+
+impl<T> Index<Vec2<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Vec2<usize>) -> &T {
+        &self[(pos.x, pos.y)]
+    }
+}
+
+impl<T> IndexMut<Vec2<usize>> for Grid<T> {
+    fn index_mut(&mut self, pos: Vec2<usize>) -> &mut T {
+        &mut self[(pos.x, pos.y)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "\
+123
+456
+789";
+
+    fn digits() -> Grid<u32> {
+        Grid::parse(MAP, |ch| ch.to_digit(10).unwrap())
+    }
+
+    fn sorted(positions: impl Iterator<Item = (usize, usize)>) -> Vec<(usize, usize)> {
+        let mut positions = positions.collect::<Vec<_>>();
+
+        positions.sort_unstable();
+        positions
+    }
+
+    #[test]
+    fn parse() {
+        let grid = digits();
+
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid[(2, 0)], 3);
+        assert_eq!(grid[Vec2::new(0, 2)], 7);
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.position(|&digit| digit == 6), Some((2, 1)));
+    }
+
+    #[test]
+    fn parse_empty() {
+        let grid = Grid::parse("", |ch| ch);
+
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.positions().count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "rows differ in width")]
+    fn parse_uneven_rows() {
+        Grid::parse("123\n45\n678", |ch| ch);
+    }
+
+    #[test]
+    fn from_vec() {
+        let grid = Grid::from_vec(2, vec![1, 2, 3, 4, 5, 6]);
+
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[1, 2], [3, 4], [5, 6]]);
+        assert_eq!(Grid::<u8>::from_vec(0, Vec::new()).height(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "cells don't fill the last row")]
+    fn from_vec_partial_row() {
+        Grid::from_vec(2, vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "grid of width 0")]
+    fn from_vec_zero_width() {
+        Grid::from_vec(0, vec![1]);
+    }
+
+    #[test]
+    fn columns() {
+        let grid = digits();
+
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), [2, 5, 8]);
+        assert_eq!(grid.columns().map(|column| column.sum::<u32>()).collect::<Vec<_>>(), [12, 15, 18]);
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 out of bounds")]
+    fn column_out_of_bounds() {
+        let _ = digits().column(3);
+    }
+
+    #[test]
+    fn neighbours_at_corners_and_edges() {
+        let grid = digits();
+
+        assert_eq!(sorted(grid.neighbours4((0, 0))), [(0, 1), (1, 0)]);
+        assert_eq!(sorted(grid.neighbours8((0, 0))), [(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(sorted(grid.neighbours4((2, 1))), [(1, 1), (2, 0), (2, 2)]);
+        assert_eq!(sorted(grid.neighbours8((2, 1))), [(1, 0), (1, 1), (1, 2), (2, 0), (2, 2)]);
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8((2, 2)).count(), 3);
+    }
+
+    #[test]
+    fn rays() {
+        let grid = digits();
+
+        assert_eq!(grid.ray((0, 0), (1, 1)).copied().collect::<Vec<_>>(), [5, 9]);
+        assert_eq!(grid.ray((1, 2), (0, -1)).copied().collect::<Vec<_>>(), [5, 2]);
+        assert_eq!(grid.ray((2, 1), (1, 0)).count(), 0);
+        assert_eq!(grid.ray_positions((0, 1), (2, 0)).collect::<Vec<_>>(), [(2, 1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "a ray needs a non-zero step")]
+    fn ray_without_a_step() {
+        let _ = digits().ray((1, 1), (0, 0));
+    }
+
+    #[test]
+    fn map() {
+        let grid = digits().map(|&digit| digit % 2 == 0);
+
+        assert_eq!(grid.iter().filter(|&(_, &even)| even).count(), 4);
+        assert!(grid[(1, 0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "(0, 3) is out of bounds")]
+    fn index_out_of_bounds() {
+        let _ = digits()[(0, 3)];
+    }
+}
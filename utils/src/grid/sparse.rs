@@ -0,0 +1,234 @@
+use std::{
+    collections::{hash_map::Entry, BTreeMap, HashMap},
+    iter::FromIterator,
+};
+use crate::geom::Vec2;
+use super::Grid;
+
+/// An unbounded grid that only stores occupied cells.
+///
+/// The bounding box of the occupied cells is kept up to date on every insertion and removal.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Vec2<i64>, T>,
+    xs: BTreeMap<i64, usize>,
+    ys: BTreeMap<i64, usize>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            xs: BTreeMap::new(),
+            ys: BTreeMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, pos: Vec2<i64>, value: T) -> Option<T> {
+        match self.cells.entry(pos) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                *self.xs.entry(pos.x).or_default() += 1;
+                *self.ys.entry(pos.y).or_default() += 1;
+
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, pos: Vec2<i64>) -> Option<T> {
+        let value = self.cells.remove(&pos)?;
+
+        decrement(&mut self.xs, pos.x);
+        decrement(&mut self.ys, pos.y);
+
+        Some(value)
+    }
+
+    pub fn get(&self, pos: Vec2<i64>) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: Vec2<i64>) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    pub fn contains(&self, pos: Vec2<i64>) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The inclusive corners of the smallest box containing every occupied cell
+    pub fn bounds(&self) -> Option<(Vec2<i64>, Vec2<i64>)> {
+        let (&min_x, _) = self.xs.iter().next()?;
+        let (&max_x, _) = self.xs.iter().next_back()?;
+        let (&min_y, _) = self.ys.iter().next()?;
+        let (&max_y, _) = self.ys.iter().next_back()?;
+
+        Some((Vec2::new(min_x, min_y), Vec2::new(max_x, max_y)))
+    }
+
+    /// The number of unoccupied cells within the bounds
+    pub fn count_empty(&self) -> u64 {
+        let Some((min, max)) = self.bounds() else {
+            return 0;
+        };
+
+        let area = (max.x - min.x + 1) as u64 * (max.y - min.y + 1) as u64;
+
+        area - self.cells.len() as u64
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Vec2<i64>> + '_ {
+        self.cells.keys().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2<i64>, &T)> {
+        self.cells.iter().map(|(pos, value)| (*pos, value))
+    }
+
+    /// Copies the cells within the bounds into a dense grid
+    pub fn to_grid(&self, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        match self.bounds() {
+            Some((min, max)) => self.window(min, max, fill),
+            None => Grid::from_vec(0, Vec::new()),
+        }
+    }
+
+    /// Copies the cells between the inclusive corners `min` and `max` into a dense grid,
+    /// with `min` ending up at `(0, 0)`
+    pub fn window(&self, min: Vec2<i64>, max: Vec2<i64>, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let width = (max.x - min.x + 1).max(0) as usize;
+        let height = (max.y - min.y + 1).max(0) as usize;
+        let mut grid = Grid::new(width, height, fill);
+
+        for (pos, value) in self.iter() {
+            let offset = pos - min;
+
+            if let Some(offset) = offset.try_cast::<usize>() {
+                if let Some(cell) = grid.get_mut((offset.x, offset.y)) {
+                    *cell = value.clone();
+                }
+            }
+        }
+
+        grid
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<(Vec2<i64>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Vec2<i64>, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(Vec2<i64>, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Vec2<i64>, T)>>(&mut self, iter: I) {
+        for (pos, value) in iter {
+            self.insert(pos, value);
+        }
+    }
+}
+
+fn decrement(counts: &mut BTreeMap<i64, usize>, key: i64) {
+    let count = counts.get_mut(&key).unwrap();
+    *count -= 1;
+
+    if *count == 0 {
+        counts.remove(&key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(cells: &[(i64, i64)]) -> SparseGrid<char> {
+        cells.iter().map(|&(x, y)| (Vec2::new(x, y), '#')).collect()
+    }
+
+    #[test]
+    fn bounds_shrink_after_removals() {
+        let mut grid = grid(&[(-3, 2), (5, -1), (0, 0), (5, 4)]);
+
+        assert_eq!(grid.bounds(), Some((Vec2::new(-3, -1), Vec2::new(5, 4))));
+
+        grid.remove(Vec2::new(5, 4));
+        assert_eq!(grid.bounds(), Some((Vec2::new(-3, -1), Vec2::new(5, 2))));
+
+        // Another cell still occupies column 5
+        grid.remove(Vec2::new(-3, 2));
+        assert_eq!(grid.bounds(), Some((Vec2::new(0, -1), Vec2::new(5, 0))));
+
+        grid.remove(Vec2::new(5, -1));
+        grid.remove(Vec2::new(0, 0));
+        assert_eq!(grid.bounds(), None);
+        assert!(grid.is_empty());
+        assert_eq!(grid.count_empty(), 0);
+    }
+
+    #[test]
+    fn overwriting_and_removing_missing_cells_keep_bounds() {
+        let mut grid = grid(&[(1, 1), (2, 3)]);
+
+        assert_eq!(grid.insert(Vec2::new(1, 1), 'x'), Some('#'));
+        assert_eq!(grid.remove(Vec2::new(7, 7)), None);
+
+        grid.remove(Vec2::new(2, 3));
+
+        assert_eq!(grid.bounds(), Some((Vec2::new(1, 1), Vec2::new(1, 1))));
+        assert_eq!(grid.get(Vec2::new(1, 1)), Some(&'x'));
+        assert_eq!(grid.len(), 1);
+    }
+
+    #[test]
+    fn count_empty() {
+        assert_eq!(grid(&[(-1, -1), (1, 1)]).count_empty(), 7);
+        assert_eq!(grid(&[(4, 4)]).count_empty(), 0);
+    }
+
+    #[test]
+    fn window_with_negative_coordinates() {
+        let grid = grid(&[(-2, -1), (0, 0), (3, 3)]);
+
+        assert_eq!(
+            grid.window(Vec2::new(-2, -1), Vec2::new(0, 0), '.'),
+            Grid::from_vec(3, vec!['#', '.', '.', '.', '.', '#']),
+        );
+        assert_eq!(
+            grid.window(Vec2::new(-3, -2), Vec2::new(-1, -1), '.'),
+            Grid::from_vec(3, vec!['.', '.', '.', '.', '#', '.']),
+        );
+        assert_eq!(grid.window(Vec2::new(0, 0), Vec2::new(-1, 5), '.').width(), 0);
+    }
+
+    #[test]
+    fn to_grid() {
+        let grid = grid(&[(-1, 0), (0, -1)]);
+
+        assert_eq!(grid.to_grid('.'), Grid::from_vec(2, vec!['.', '#', '#', '.']));
+        assert_eq!(SparseGrid::<char>::new().to_grid('.').height(), 0);
+    }
+}
@@ -0,0 +1,428 @@
+use std::{
+    fmt,
+    iter::FromIterator,
+    ops::{Range, RangeInclusive},
+};
+use num_traits::PrimInt;
+
+/// A range of integers, stored as `start..end`
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    /// The interval `start..end`, which doesn't contain `end`.
+    /// Every empty interval equals `Interval::empty()`.
+    pub fn new(start: T, end: T) -> Self {
+        if end <= start {
+            return Self::empty();
+        }
+
+        Self { start, end }
+    }
+
+    /// The interval `start..=last`.
+    ///
+    /// As the end is stored exclusively, `T::MAX` can't be contained,
+    /// so a `last` of `T::MAX` is clamped to `start..T::MAX`.
+    pub fn inclusive(start: T, last: T) -> Self {
+        let end = last.checked_add(&T::one()).unwrap_or(last);
+
+        Self::new(start, end)
+    }
+
+    /// The empty interval `0..0`
+    pub fn empty() -> Self {
+        Self {
+            start: T::zero(),
+            end: T::zero(),
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    /// The first value after the interval
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    /// The last value within the interval
+    pub fn last(&self) -> Option<T> {
+        (!self.is_empty()).then(|| self.end - T::one())
+    }
+
+    pub fn len(&self) -> T {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// The smallest interval covering both, if they overlap or touch
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        if self.is_empty() {
+            return Some(*other);
+        }
+
+        if other.is_empty() {
+            return Some(*self);
+        }
+
+        if self.start > other.end || other.start > self.end {
+            return None;
+        }
+
+        Some(Self::new(self.start.min(other.start), self.end.max(other.end)))
+    }
+
+    /// The parts of `self` before and after `other`
+    pub fn difference(&self, other: &Self) -> (Self, Self) {
+        let before = Self::new(self.start, self.end.min(other.start));
+        let after = Self::new(self.start.max(other.end), self.end);
+
+        (before, after)
+    }
+}
+
+impl<T: PrimInt> From<Range<T>> for Interval<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for Interval<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        Self::inclusive(*range.start(), *range.end())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}..{:?}", self.start, self.end)
+    }
+}
+
+/// A set of integers, stored as sorted, disjoint and non-touching intervals
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Adds the interval, merging it with any intervals it overlaps or touches
+    pub fn insert(&mut self, interval: impl Into<Interval<T>>) {
+        let mut interval = interval.into();
+
+        if interval.is_empty() {
+            return;
+        }
+
+        let first = self.intervals.partition_point(|other| other.end < interval.start);
+        let last = first + self.intervals[first..].partition_point(|other| other.start <= interval.end);
+
+        for other in &self.intervals[first..last] {
+            interval = interval.union(other).unwrap();
+        }
+
+        self.intervals.splice(first..last, Some(interval));
+    }
+
+    pub fn remove(&mut self, interval: impl Into<Interval<T>>) {
+        let interval = interval.into();
+
+        if interval.is_empty() {
+            return;
+        }
+
+        let first = self.intervals.partition_point(|other| other.end <= interval.start);
+        let last = first + self.intervals[first..].partition_point(|other| other.start < interval.end);
+        let remainders = self.intervals[first..last]
+            .iter()
+            .flat_map(|other| {
+                let (before, after) = other.difference(&interval);
+
+                vec![before, after]
+            })
+            .filter(|remainder| !remainder.is_empty())
+            .collect::<Vec<_>>();
+
+        self.intervals.splice(first..last, remainders);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|interval| interval.end <= value);
+
+        self.intervals
+            .get(index)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        union.extend(other.iter());
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            let overlap = a.intersection(b);
+
+            if !overlap.is_empty() {
+                intersection.push(overlap);
+            }
+
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self {
+            intervals: intersection,
+        }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+
+        for interval in other.iter() {
+            difference.remove(interval);
+        }
+
+        difference
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.intervals.iter().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of integers in the set
+    pub fn covered_len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |len, interval| len + interval.len())
+    }
+
+    /// The intervals between consecutive intervals of the set
+    pub fn gaps(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.intervals
+            .windows(2)
+            .map(|pair| Interval::new(pair[0].end, pair[1].start))
+    }
+
+    /// The parts of `bounds` not covered by the set
+    pub fn gaps_within(&self, bounds: impl Into<Interval<T>>) -> Self {
+        let mut bounds_set = Self::new();
+        bounds_set.insert(bounds);
+        bounds_set.difference(self)
+    }
+
+    /// The smallest value within `bounds` that isn't in the set
+    pub fn first_uncovered(&self, bounds: impl Into<Interval<T>>) -> Option<T> {
+        let bounds = bounds.into();
+
+        if bounds.is_empty() {
+            return None;
+        }
+
+        let index = self.intervals.partition_point(|interval| interval.end <= bounds.start);
+        let candidate = match self.intervals.get(index) {
+            Some(interval) if interval.contains(bounds.start) => interval.end,
+            _ => bounds.start,
+        };
+
+        bounds.contains(candidate).then_some(candidate)
+    }
+}
+
+impl<T: PrimInt> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PrimInt, I: Into<Interval<T>>> Extend<I> for IntervalSet<T> {
+    fn extend<It: IntoIterator<Item = I>>(&mut self, iter: It) {
+        for interval in iter {
+            self.insert(interval);
+        }
+    }
+}
+
+impl<T: PrimInt, I: Into<Interval<T>>> FromIterator<I> for IntervalSet<T> {
+    fn from_iter<It: IntoIterator<Item = I>>(iter: It) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(&self.intervals).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn intervals(set: &IntervalSet<i32>) -> Vec<Interval<i32>> {
+        set.iter().collect()
+    }
+
+    #[test]
+    fn interval_basics() {
+        let interval = Interval::from(2..=5);
+
+        assert_eq!((interval.start(), interval.end(), interval.last()), (2, 6, Some(5)));
+        assert_eq!(interval.len(), 4);
+        assert!(interval.contains(5) && !interval.contains(6));
+        assert_eq!(Interval::new(5, 2), Interval::new(5, 5));
+        assert_eq!(Interval::new(5, 5), Interval::empty());
+        assert_eq!(Interval::inclusive(3, 2), Interval::empty());
+        assert_eq!(Interval::from(-3..3).intersection(&Interval::from(7..9)), Interval::empty());
+        assert_eq!(Interval::<i32>::empty().last(), None);
+        assert_eq!(interval.union(&Interval::from(6..8)), Some(Interval::new(2, 8)));
+        assert_eq!(interval.union(&Interval::from(7..8)), None);
+        assert_eq!(interval.difference(&Interval::from(3..4)), (Interval::new(2, 3), Interval::new(4, 6)));
+    }
+
+    #[test]
+    fn inclusive_intervals_saturate() {
+        let interval = Interval::<u8>::inclusive(250, u8::MAX);
+
+        assert_eq!((interval.start(), interval.end(), interval.last()), (250, 255, Some(254)));
+        assert!(!interval.contains(u8::MAX));
+        assert_eq!(Interval::from(0..=i32::MAX).len(), i32::MAX);
+        assert!(Interval::<u8>::inclusive(u8::MAX, u8::MAX).is_empty());
+    }
+
+    #[test]
+    fn insert_merges_overlapping_and_touching() {
+        let mut set = IntervalSet::new();
+
+        set.insert(10..12);
+        set.insert(0..3);
+        set.insert(5..7);
+        assert_eq!(intervals(&set), [Interval::new(0, 3), Interval::new(5, 7), Interval::new(10, 12)]);
+
+        set.insert(3..5);
+        assert_eq!(intervals(&set), [Interval::new(0, 7), Interval::new(10, 12)]);
+
+        set.insert(6..11);
+        set.insert(20..20);
+        assert_eq!(intervals(&set), [Interval::new(0, 12)]);
+        assert_eq!(set.covered_len(), 12);
+    }
+
+    #[test]
+    fn remove_splits_intervals() {
+        let mut set = vec![0..10, 20..30].into_iter().collect::<IntervalSet<i32>>();
+
+        set.remove(3..5);
+        assert_eq!(intervals(&set), [Interval::new(0, 3), Interval::new(5, 10), Interval::new(20, 30)]);
+
+        set.remove(8..25);
+        assert_eq!(intervals(&set), [Interval::new(0, 3), Interval::new(5, 8), Interval::new(25, 30)]);
+
+        set.remove(-5..0);
+        set.remove(30..40);
+        assert_eq!(set.covered_len(), 11);
+
+        set.remove(0..30);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn first_uncovered() {
+        let set = vec![0..5, 6..10].into_iter().collect::<IntervalSet<i32>>();
+
+        assert_eq!(set.first_uncovered(0..20), Some(5));
+        assert_eq!(set.first_uncovered(6..20), Some(10));
+        assert_eq!(set.first_uncovered(-3..20), Some(-3));
+        assert_eq!(set.first_uncovered(5..6), Some(5));
+        assert_eq!(set.first_uncovered(6..10), None);
+        assert_eq!(set.first_uncovered(7..7), None);
+        assert_eq!(IntervalSet::new().first_uncovered(4..=4), Some(4));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = vec![0..5, 10..15].into_iter().collect::<IntervalSet<i32>>();
+        let b = IntervalSet::from_iter(Some(3..12));
+
+        assert_eq!(intervals(&a.union(&b)), [Interval::new(0, 15)]);
+        assert_eq!(intervals(&a.intersection(&b)), [Interval::new(3, 5), Interval::new(10, 12)]);
+        assert_eq!(intervals(&a.difference(&b)), [Interval::new(0, 3), Interval::new(12, 15)]);
+        assert_eq!(a.gaps().collect::<Vec<_>>(), [Interval::new(5, 10)]);
+        assert_eq!(intervals(&a.gaps_within(-2..12)), [Interval::new(-2, 0), Interval::new(5, 10)]);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_a_bitmap(ops in prop::collection::vec((any::<bool>(), 0..64i32, 0..64i32), 0..40)) {
+            let mut set = IntervalSet::new();
+            let mut model = [false; 64];
+
+            for (insert, start, end) in ops {
+                if insert {
+                    set.insert(start..end);
+                } else {
+                    set.remove(start..end);
+                }
+
+                for covered in &mut model[start as usize..end.max(start) as usize] {
+                    *covered = insert;
+                }
+            }
+
+            for (value, &covered) in model.iter().enumerate() {
+                prop_assert_eq!(set.contains(value as i32), covered);
+            }
+
+            let sorted_and_apart = set.iter().zip(set.iter().skip(1)).all(|(a, b)| a.end() < b.start());
+
+            prop_assert!(sorted_and_apart);
+            prop_assert!(set.iter().all(|interval| !interval.is_empty()));
+            prop_assert_eq!(set.covered_len(), model.iter().filter(|&&covered| covered).count() as i32);
+            prop_assert_eq!(set.first_uncovered(0..64), model.iter().position(|&covered| !covered).map(|i| i as i32));
+        }
+    }
+}
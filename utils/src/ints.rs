@@ -0,0 +1,167 @@
+use std::{
+    convert::TryInto,
+    marker::PhantomData,
+};
+use crate::ParseError;
+
+pub trait Int: Copy {
+    /// Whether a leading `-` belongs to the number
+    const SIGNED: bool;
+
+    fn parse_int(s: &str) -> Result<Self, ParseError>;
+}
+
+/// An integer type that takes a leading `-`, for `StrExt::ints`
+pub trait SignedInt: Int {}
+
+/// An integer type without a sign, for `StrExt::uints`
+pub trait UnsignedInt: Int {}
+
+macro_rules! impl_int {
+    ($signed:expr, $kind:ident => $($ty:ty),*) => {
+        $(
+            impl Int for $ty {
+                const SIGNED: bool = $signed;
+
+                fn parse_int(s: &str) -> Result<Self, ParseError> {
+                    s.parse().map_err(|err| ParseError::new(s, stringify!($ty)).with_reason(err))
+                }
+            }
+
+            impl $kind for $ty {}
+        )*
+    };
+}
+
+impl_int!(false, UnsignedInt => u8, u16, u32, u64, u128, usize);
+impl_int!(true, SignedInt => i8, i16, i32, i64, i128, isize);
+
+/// Iterator over the integers embedded in a string.
+///
+/// A `-` only counts as a sign if it is not preceded by a digit,
+/// so `2-4` yields `2` and `4`, while `x=-2` yields `-2`.
+/// Unsigned types never take a sign, so `x=-2` yields `2` for them.
+///
+/// `StrExt::ints` yields signed and `StrExt::uints` unsigned integers,
+/// while `StrExt::ints_n` takes either.
+pub struct Ints<'a, T> {
+    s: &'a str,
+    pos: usize,
+    signed: bool,
+    _ty: PhantomData<T>,
+}
+
+impl<'a, T: Int> Ints<'a, T> {
+    pub(crate) fn signed(s: &'a str) -> Self {
+        Self {
+            s,
+            pos: 0,
+            signed: true,
+            _ty: PhantomData,
+        }
+    }
+
+    pub(crate) fn unsigned(s: &'a str) -> Self {
+        Self {
+            signed: false,
+            ..Self::signed(s)
+        }
+    }
+
+    pub fn try_next(&mut self) -> Option<Result<T, ParseError>> {
+        let bytes = self.s.as_bytes();
+        let digits_start = self.pos + bytes[self.pos..].iter().position(u8::is_ascii_digit)?;
+        let digits_len = bytes[digits_start..].iter().take_while(|b| b.is_ascii_digit()).count();
+        let digits_end = digits_start + digits_len;
+
+        let is_negative = self.signed
+            && T::SIGNED
+            && digits_start > 0
+            && bytes[digits_start - 1] == b'-'
+            && (digits_start < 2 || !bytes[digits_start - 2].is_ascii_digit());
+        let start = if is_negative { digits_start - 1 } else { digits_start };
+        let number = &self.s[start..digits_end];
+
+        self.pos = digits_end;
+
+        Some(T::parse_int(number))
+    }
+
+    /// Collects exactly `N` integers, failing if there are more or fewer.
+    pub fn try_collect_n<const N: usize>(mut self) -> Result<[T; N], ParseError> {
+        let s = self.s;
+        let mut ints = Vec::with_capacity(N);
+
+        while let Some(int) = self.try_next() {
+            ints.push(int?);
+        }
+
+        let count = ints.len();
+
+        ints.try_into().map_err(|_| {
+            ParseError::new(s, "a fixed number of integers")
+                .with_reason(format!("expected {}, found {}", N, count))
+        })
+    }
+}
+
+/// Panics if an integer doesn't fit into `T`, use `try_next` to handle that
+impl<T: Int> Iterator for Ints<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.try_next().map(|int| int.unwrap_or_else(|err| panic!("{}", err)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::StrExt;
+
+    #[test]
+    fn signs() {
+        assert_eq!("2-4".ints::<i32>().collect::<Vec<_>>(), [2, 4]);
+        assert_eq!("x=-2".ints::<i32>().collect::<Vec<_>>(), [-2]);
+        assert_eq!(",-3".ints::<i64>().collect::<Vec<_>>(), [-3]);
+        assert_eq!("-1".ints::<i8>().collect::<Vec<_>>(), [-1]);
+        assert_eq!("5--3".ints::<i32>().collect::<Vec<_>>(), [5, -3]);
+    }
+
+    #[test]
+    fn unsigned_types_ignore_signs() {
+        assert_eq!("x=-5, y=3".uints::<u64>().collect::<Vec<_>>(), [5, 3]);
+        assert_eq!("x=-5, y=3".ints_n::<u32, 2>().unwrap(), [5, 3]);
+        assert_eq!("x=-5, y=3".ints_n::<i32, 2>().unwrap(), [-5, 3]);
+    }
+
+    #[test]
+    fn sensor_line() {
+        let line = "Sensor at x=2, y=-18: closest beacon is at x=-2, y=15";
+
+        assert_eq!(line.ints_n::<i64, 4>().unwrap(), [2, -18, -2, 15]);
+        assert_eq!("move 13 from 1 to 2".ints_n::<usize, 3>().unwrap(), [13, 1, 2]);
+        assert_eq!("170141183460469231731687303715884105727".ints_n::<i128, 1>().unwrap(), [i128::MAX]);
+    }
+
+    #[test]
+    fn count_mismatch() {
+        let err = "1 2 3".ints_n::<u32, 2>().unwrap_err();
+
+        assert_eq!(err.reason(), Some("expected 2, found 3"));
+        assert_eq!("1".ints_n::<u32, 2>().unwrap_err().reason(), Some("expected 2, found 1"));
+        assert!("".ints_n::<u32, 0>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn overflow() {
+        assert!("300".ints_n::<u8, 1>().is_err());
+        assert!("1 300".uints::<u8>().try_next().unwrap().is_ok());
+        assert!("-129".ints::<i8>().try_next().unwrap().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "expected u8, found \"300\"")]
+    fn iterator_panics_on_overflow() {
+        "300".uints::<u8>().for_each(drop);
+    }
+}
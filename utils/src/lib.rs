@@ -1,46 +1,90 @@
 use std::{
-    collections::BTreeMap,
-    fmt::Debug,
+    any::type_name,
+    fmt::{Debug, Display},
     str::FromStr,
-    sync::Mutex,
 };
-use regex::Captures;
 
-#[doc(hidden)] pub use lazy_static::lazy_static;
-#[doc(hidden)] pub use regex::Regex;
-
-#[macro_export]
-macro_rules! re {
-    ($re:expr, $str:expr) => {{
-        let re = re!($re);
-        re.captures($str).unwrap()
-    }};
-    ($re:expr) => {{
-        $crate::lazy_static! {
-            static ref RE: $crate::Regex = $crate::Regex::new($re).unwrap();
-        }
+mod direction;
+pub use direction::{Direction4, Direction8};
 
-        &*RE
-    }};
-}
+pub mod bitset;
+pub mod cycle;
+pub mod expr;
+pub mod geom;
+pub mod grid;
+
+pub mod interval;
+pub mod modular;
+pub mod optimize;
+pub mod radix;
+
+mod ints;
+pub use ints::{Int, Ints, SignedInt, UnsignedInt};
+
+mod parse;
+pub use parse::{parse_lines, try_parse_lines, Location, ParseError};
+
+pub mod render;
+pub mod search;
+pub mod tree;
+
+mod regex_cache;
+pub use regex_cache::{regex_cache_stats, set_regex_cache_capacity, RegexCacheStats};
+use regex_cache::with_cached_regex;
+
+#[doc(hidden)] pub mod scan;
+
+pub use utils_derive::{re, scan, try_scan, FromRegex};
+
+#[doc(hidden)] pub use lazy_static::lazy_static;
+#[doc(hidden)] pub use regex::{Captures, Regex};
 
 pub trait CapturesExt<'a> {
     fn str(self, name: &str) -> &'a str;
     fn parse<T: FromStr>(self, name: &str) -> T
     where
         T::Err: Debug;
+    fn try_str(self, name: &str) -> Result<&'a str, ParseError>;
+    fn try_parse<T: FromStr>(self, name: &str) -> Result<T, ParseError>
+    where
+        T::Err: Display;
 }
 
 impl<'a> CapturesExt<'a> for &'_ Captures<'a> {
     fn str(self, name: &str) -> &'a str {
-        self.name(name).unwrap().as_str()
+        self.try_str(name).unwrap_or_else(|err| panic!("{}", err))
     }
 
     fn parse<T: FromStr>(self, name: &str) -> T
     where
         T::Err: Debug
     {
-        self.str(name).parse().unwrap()
+        let s = self.str(name);
+
+        s.parse().unwrap_or_else(|err| {
+            let err = ParseError::new(s, type_name::<T>()).with_reason(format!("{err:?}"));
+
+            panic!("{}", err)
+        })
+    }
+
+    fn try_str(self, name: &str) -> Result<&'a str, ParseError> {
+        match self.name(name) {
+            Some(group) => Ok(group.as_str()),
+            None => {
+                let whole_match = self.get(0).map_or("", |m| m.as_str());
+
+                Err(ParseError::new(whole_match, "a match for the capture group")
+                    .with_reason(format!("group {name:?} did not participate")))
+            }
+        }
+    }
+
+    fn try_parse<T: FromStr>(self, name: &str) -> Result<T, ParseError>
+    where
+        T::Err: Display
+    {
+        try_parse_str(self.try_str(name)?)
     }
 }
 
@@ -51,6 +95,14 @@ pub trait StrExt {
     fn usize(&self) -> usize;
     fn char(&self) -> char;
     fn is_match(&self, regex: &str) -> bool;
+    fn try_u8(&self) -> Result<u8, ParseError>;
+    fn try_i32(&self) -> Result<i32, ParseError>;
+    fn try_u32(&self) -> Result<u32, ParseError>;
+    fn try_usize(&self) -> Result<usize, ParseError>;
+    fn try_char(&self) -> Result<char, ParseError>;
+    fn ints<T: SignedInt>(&self) -> Ints<'_, T>;
+    fn uints<T: UnsignedInt>(&self) -> Ints<'_, T>;
+    fn ints_n<T: Int, const N: usize>(&self) -> Result<[T; N], ParseError>;
 }
 
 impl<S: AsRef<str>> StrExt for S {
@@ -77,29 +129,59 @@ impl<S: AsRef<str>> StrExt for S {
     fn is_match(&self, regex: &str) -> bool {
         self.as_ref().is_match(regex)
     }
+
+    fn try_u8(&self) -> Result<u8, ParseError> {
+        self.as_ref().try_u8()
+    }
+
+    fn try_i32(&self) -> Result<i32, ParseError> {
+        self.as_ref().try_i32()
+    }
+
+    fn try_u32(&self) -> Result<u32, ParseError> {
+        self.as_ref().try_u32()
+    }
+
+    fn try_usize(&self) -> Result<usize, ParseError> {
+        self.as_ref().try_usize()
+    }
+
+    fn try_char(&self) -> Result<char, ParseError> {
+        self.as_ref().try_char()
+    }
+
+    fn ints<T: SignedInt>(&self) -> Ints<'_, T> {
+        self.as_ref().ints()
+    }
+
+    fn uints<T: UnsignedInt>(&self) -> Ints<'_, T> {
+        self.as_ref().uints()
+    }
+
+    fn ints_n<T: Int, const N: usize>(&self) -> Result<[T; N], ParseError> {
+        self.as_ref().ints_n()
+    }
 }
 
 impl StrExt for str {
     fn u8(&self) -> u8 {
-        self.parse().unwrap()
+        self.try_u8().unwrap_or_else(|err| panic!("{}", err))
     }
 
     fn i32(&self) -> i32 {
-        self.parse().unwrap()
+        self.try_i32().unwrap_or_else(|err| panic!("{}", err))
     }
 
     fn u32(&self) -> u32 {
-        self.parse().unwrap()
+        self.try_u32().unwrap_or_else(|err| panic!("{}", err))
     }
 
     fn usize(&self) -> usize {
-        self.parse().unwrap()
+        self.try_usize().unwrap_or_else(|err| panic!("{}", err))
     }
 
     fn char(&self) -> char {
-        assert_eq!(self.len(), 1);
-
-        self.chars().next().unwrap()
+        self.try_char().unwrap_or_else(|err| panic!("{}", err))
     }
 
     fn is_match(&self, regex: &str) -> bool {
@@ -107,27 +189,50 @@ impl StrExt for str {
             regex.is_match(self.as_ref())
         })
     }
-}
 
-// TODO: optimize for concurrent access
-fn with_cached_regex<F, R>(regex: &str, f: F) -> R
-where
-    F: FnOnce(&Regex) -> R,
-{
-    let mut regex_cache = REGEX_CACHE.lock().unwrap();
-
-    let regex = match regex_cache.get(regex) {
-        Some(regex) => regex,
-        None => {
-            let compiled_regex = Regex::new(regex).unwrap();
-            regex_cache.insert(regex.into(), compiled_regex);
-            regex_cache.get(regex).unwrap()
+    fn try_u8(&self) -> Result<u8, ParseError> {
+        try_parse_str(self)
+    }
+
+    fn try_i32(&self) -> Result<i32, ParseError> {
+        try_parse_str(self)
+    }
+
+    fn try_u32(&self) -> Result<u32, ParseError> {
+        try_parse_str(self)
+    }
+
+    fn try_usize(&self) -> Result<usize, ParseError> {
+        try_parse_str(self)
+    }
+
+    fn try_char(&self) -> Result<char, ParseError> {
+        let mut chars = self.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => Ok(ch),
+            _ => Err(ParseError::new(self, "char")
+                .with_reason("expected exactly one character")),
         }
-    };
+    }
 
-    f(regex)
+    fn ints<T: SignedInt>(&self) -> Ints<'_, T> {
+        Ints::signed(self)
+    }
+
+    fn uints<T: UnsignedInt>(&self) -> Ints<'_, T> {
+        Ints::unsigned(self)
+    }
+
+    fn ints_n<T: Int, const N: usize>(&self) -> Result<[T; N], ParseError> {
+        Ints::signed(self).try_collect_n()
+    }
 }
 
-lazy_static! {
-    static ref REGEX_CACHE: Mutex<BTreeMap<String, Regex>> = Mutex::default();
+fn try_parse_str<T: FromStr>(s: &str) -> Result<T, ParseError>
+where
+    T::Err: Display,
+{
+    s.parse()
+        .map_err(|err| ParseError::new(s, type_name::<T>()).with_reason(err))
 }
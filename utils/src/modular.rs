@@ -0,0 +1,399 @@
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// Where a `ModInt` gets its modulus from
+pub trait Modulus: Copy + Eq {
+    fn modulus(self) -> u64;
+}
+
+/// A modulus known at compile time
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Const<const M: u64>;
+
+/// A modulus only known at runtime, which is stored in every value
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Dynamic(u64);
+
+impl<const M: u64> Modulus for Const<M> {
+    fn modulus(self) -> u64 {
+        M
+    }
+}
+
+impl Modulus for Dynamic {
+    fn modulus(self) -> u64 {
+        self.0
+    }
+}
+
+/// An integer modulo `M`, whose arithmetic never overflows.
+///
+/// Combining two values with different moduli panics.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ModInt<M> {
+    value: u64,
+    modulus: M,
+}
+
+impl<const M: u64> ModInt<Const<M>> {
+    pub fn new(value: u64) -> Self {
+        Self::with_modulus(value, Const)
+    }
+}
+
+impl ModInt<Dynamic> {
+    pub fn new(value: u64, modulus: u64) -> Self {
+        Self::with_modulus(value, Dynamic(modulus))
+    }
+}
+
+impl<M: Modulus> ModInt<M> {
+    fn with_modulus(value: u64, modulus: M) -> Self {
+        assert!(modulus.modulus() > 0, "modulus must be positive");
+
+        Self {
+            value: value % modulus.modulus(),
+            modulus,
+        }
+    }
+
+    /// A value with the same modulus as `self`
+    fn with_value(self, value: u64) -> Self {
+        debug_assert!(value < self.modulus(), "unreduced value {} modulo {}", value, self.modulus());
+
+        Self {
+            value,
+            modulus: self.modulus,
+        }
+    }
+
+    pub fn value(self) -> u64 {
+        self.value
+    }
+
+    pub fn modulus(self) -> u64 {
+        self.modulus.modulus()
+    }
+
+    pub fn pow(self, exp: u64) -> Self {
+        self.with_value(pow_mod(self.value, exp, self.modulus()))
+    }
+
+    /// The multiplicative inverse, which exists if the value is coprime to the modulus
+    pub fn inverse(self) -> Option<Self> {
+        mod_inverse(self.value, self.modulus()).map(|inverse| self.with_value(inverse))
+    }
+
+    fn check_modulus(self, rhs: Self) {
+        assert!(
+            self.modulus == rhs.modulus,
+            "mismatched moduli {} and {}",
+            self.modulus(),
+            rhs.modulus(),
+        );
+    }
+}
+
+impl<M: Modulus> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.check_modulus(rhs);
+
+        let (sum, overflowed) = self.value.overflowing_add(rhs.value);
+
+        if overflowed || sum >= self.modulus() {
+            self.with_value(sum.wrapping_sub(self.modulus()))
+        } else {
+            self.with_value(sum)
+        }
+    }
+}
+
+impl<M: Modulus> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<M: Modulus> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.check_modulus(rhs);
+        self.with_value(mul_mod(self.value, rhs.value, self.modulus()))
+    }
+}
+
+impl<M: Modulus> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        if self.value == 0 {
+            self
+        } else {
+            self.with_value(self.modulus() - self.value)
+        }
+    }
+}
+
+macro_rules! impl_ops {
+    ($($op:ident::$method:ident, $op_assign:ident::$method_assign:ident;)*) => {
+        $(
+            impl<M: Modulus> $op<u64> for ModInt<M> {
+                type Output = Self;
+
+                fn $method(self, rhs: u64) -> Self {
+                    self.$method(Self::with_modulus(rhs, self.modulus))
+                }
+            }
+
+            impl<M: Modulus> $op_assign for ModInt<M> {
+                fn $method_assign(&mut self, rhs: Self) {
+                    *self = self.$method(rhs);
+                }
+            }
+
+            impl<M: Modulus> $op_assign<u64> for ModInt<M> {
+                fn $method_assign(&mut self, rhs: u64) {
+                    *self = self.$method(rhs);
+                }
+            }
+        )*
+    };
+}
+
+impl_ops! {
+    Add::add, AddAssign::add_assign;
+    Sub::sub, SubAssign::sub_assign;
+    Mul::mul, MulAssign::mul_assign;
+}
+
+impl<M: Modulus> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl<M: Modulus> fmt::Debug for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (mod {})", self.value, self.modulus())
+    }
+}
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let rem = a % b;
+        a = b;
+        b = rem;
+    }
+
+    a
+}
+
+/// The least common multiple, panicking if it doesn't fit into a `u64`
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+
+    (a / gcd(a, b)).checked_mul(b).expect("lcm overflows u64")
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y == g`, where `g` is the non-negative gcd of `a` and `b`
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let quotient = old_r / r;
+
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `a * b % m`, computed through `u128` so it can't overflow
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+pub fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    base %= m;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+
+    result
+}
+
+/// The `x` in `0..m` with `a * x % m == 1`, if `a` and `m` are coprime.
+/// A zero modulus has no inverses.
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+
+    let (g, x, _) = extended_gcd(a as i128, m as i128);
+
+    (g == 1).then(|| x.rem_euclid(m as i128) as u64 % m)
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)`, returning the smallest
+/// non-negative solution along with the modulus it repeats with. The moduli don't need to be coprime.
+///
+/// Returns `None` if the congruences contradict each other, any modulus is zero,
+/// or the combined modulus doesn't fit into a `u64`.
+pub fn crt(congruences: impl IntoIterator<Item = (u64, u64)>) -> Option<(u64, u64)> {
+    let mut solution = (0, 1);
+
+    for (residue, modulus) in congruences {
+        if modulus == 0 {
+            return None;
+        }
+
+        solution = merge_congruences(solution, (residue % modulus, modulus))?;
+    }
+
+    Some(solution)
+}
+
+fn merge_congruences((r1, m1): (u64, u64), (r2, m2): (u64, u64)) -> Option<(u64, u64)> {
+    let g = gcd(m1, m2);
+    let diff = r2 as i128 - r1 as i128;
+
+    if diff % g as i128 != 0 {
+        return None;
+    }
+
+    let m2_reduced = m2 / g;
+    let combined = (m1 / g).checked_mul(m2)?;
+    // x = r1 + m1 * t, where m1 * t ≡ diff (mod m2), so t ≡ diff / g * (m1 / g)^-1 (mod m2 / g)
+    let diff_reduced = (diff / g as i128).rem_euclid(m2_reduced as i128) as u64;
+    let inverse = mod_inverse((m1 / g) % m2_reduced, m2_reduced).unwrap();
+    let t = mul_mod(diff_reduced, inverse, m2_reduced);
+    let x = (r1 as u128 + m1 as u128 * t as u128) % combined as u128;
+
+    Some((x as u64, combined))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extended_gcd_satisfies_bezout() {
+        for (a, b) in [(240, 46), (46, 240), (-240, 46), (240, -46), (0, 7), (7, 0), (0, 0), (17, 17)] {
+            let (g, x, y) = extended_gcd(a, b);
+
+            assert_eq!(a * x + b * y, g, "{} * {} + {} * {}", a, x, b, y);
+            assert_eq!(g, gcd(a.unsigned_abs() as u64, b.unsigned_abs() as u64) as i128);
+        }
+    }
+
+    #[test]
+    fn inverses() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(10, 17), Some(12));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(0, 7), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+        assert_eq!(mod_inverse(1, 0), None);
+        assert_eq!(mod_inverse(5, 0), None);
+
+        for a in 1..13 {
+            let inverse = mod_inverse(a, 13).unwrap();
+
+            assert_eq!(a * inverse % 13, 1);
+        }
+    }
+
+    #[test]
+    fn crt_with_coprime_moduli() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(5, 3)]), Some((2, 3)));
+        assert_eq!(crt([]), Some((0, 1)));
+    }
+
+    #[test]
+    fn crt_with_shared_factors() {
+        assert_eq!(crt([(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([(7, 12), (1, 6)]), Some((7, 12)));
+    }
+
+    #[test]
+    fn crt_rejects_overflowing_moduli() {
+        let big_prime = 18446744073709551557;
+
+        assert_eq!(crt([(1, big_prime), (1, 3)]), None);
+        assert_eq!(crt([(1, big_prime), (1, big_prime)]), Some((1, big_prime)));
+        assert_eq!(crt([(1, 1 << 40), (1, 1 << 30)]), Some((1, 1 << 40)));
+    }
+
+    #[test]
+    fn crt_rejects_zero_moduli() {
+        assert_eq!(crt([(1, 0)]), None);
+        assert_eq!(crt([(2, 3), (0, 0)]), None);
+    }
+
+    #[test]
+    fn mod_int_wraps_around() {
+        const BIG: u64 = u64::MAX - 58;
+
+        let a = ModInt::<Const<BIG>>::new(BIG - 1);
+        let b = ModInt::<Const<BIG>>::new(2);
+
+        assert_eq!((a + b).value(), 1);
+        assert_eq!((b - a).value(), 3);
+        assert_eq!((a * a).value(), 1);
+        assert_eq!((-b).value(), BIG - 2);
+        assert_eq!(ModInt::<Const<BIG>>::new(u64::MAX).value(), 58);
+
+        let x = ModInt::<Dynamic>::new(7, 10);
+
+        assert_eq!((x * 3).value(), 1);
+        assert_eq!(x.pow(4).value(), 1);
+        assert_eq!(x.inverse(), Some(ModInt::<Dynamic>::new(3, 10)));
+        assert_eq!(ModInt::<Dynamic>::new(4, 10).inverse(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "mismatched moduli 7 and 5")]
+    fn mismatched_moduli() {
+        let _ = ModInt::<Dynamic>::new(1, 7) + ModInt::<Dynamic>::new(1, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must be positive")]
+    fn zero_modulus() {
+        ModInt::<Dynamic>::new(1, 0);
+    }
+
+    #[test]
+    fn lcm_and_pow_mod() {
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+        assert_eq!(pow_mod(2, 10, 1000), 24);
+        assert_eq!(pow_mod(5, 0, 1), 0);
+    }
+}
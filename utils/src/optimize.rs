@@ -0,0 +1,364 @@
+//! Branch-and-bound maximisation over implicit state spaces, described by closures.
+//!
+//! Every state is a solution in its own right, worth `value(state)`. Successors may only
+//! add to that value, and `upper_bound(state)` must never underestimate the best value
+//! reachable from a state, otherwise the optimum may be pruned away.
+
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry as MapEntry, BinaryHeap, HashMap},
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Strategy {
+    /// Explores the most recently found state first, which finds good solutions quickly
+    #[default]
+    DepthFirst,
+    /// Explores the state with the highest upper bound first
+    BestFirst,
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct BranchAndBound {
+    strategy: Strategy,
+    node_budget: Option<usize>,
+    time_budget: Option<Duration>,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// States whose successors were generated
+    pub explored: usize,
+    /// States discarded because of their upper bound or a dominating state with the same key
+    pub pruned: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome<S, V> {
+    pub best: S,
+    pub value: V,
+    pub stats: Stats,
+    /// Whether the search ran to completion, proving that `best` is optimal
+    pub complete: bool,
+}
+
+impl BranchAndBound {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Stops after exploring this many states
+    pub fn node_budget(mut self, nodes: usize) -> Self {
+        self.node_budget = Some(nodes);
+        self
+    }
+
+    /// Stops after running for this long
+    pub fn time_budget(mut self, time: Duration) -> Self {
+        self.time_budget = Some(time);
+        self
+    }
+
+    /// Finds the most valuable state reachable from `start`.
+    ///
+    /// States with the same `key` must have the same future, so only
+    /// the most valuable of them seen so far is explored.
+    pub fn maximize<S, K, V, I>(
+        &self,
+        start: S,
+        mut successors: impl FnMut(&S) -> I,
+        mut value: impl FnMut(&S) -> V,
+        mut upper_bound: impl FnMut(&S) -> V,
+        mut key: impl FnMut(&S) -> K,
+    ) -> Outcome<S, V>
+    where
+        S: Clone,
+        K: Clone + Eq + Hash,
+        V: Copy + Ord,
+        I: IntoIterator<Item = S>,
+    {
+        let started = Instant::now();
+        let mut stats = Stats::default();
+        let mut best_value = value(&start);
+        let mut best = start.clone();
+        let mut memo = HashMap::new();
+        let mut frontier = Frontier::new(self.strategy);
+        let start_key = key(&start);
+
+        memo.insert(start_key.clone(), best_value);
+        frontier.push(Candidate {
+            bound: upper_bound(&start),
+            value: best_value,
+            key: start_key,
+            state: start,
+        });
+
+        while let Some(candidate) = frontier.pop() {
+            if self.over_budget(&stats, started) {
+                return Outcome {
+                    best,
+                    value: best_value,
+                    stats,
+                    complete: false,
+                };
+            }
+
+            // The bound may have been beaten, or the key reached more valuably, since the candidate was queued
+            if candidate.bound <= best_value || memo[&candidate.key] > candidate.value {
+                stats.pruned += 1;
+                continue;
+            }
+
+            stats.explored += 1;
+
+            for next in successors(&candidate.state) {
+                let next_value = value(&next);
+
+                if next_value > best_value {
+                    best_value = next_value;
+                    best = next.clone();
+                }
+
+                let next_key = key(&next);
+
+                match memo.entry(next_key.clone()) {
+                    MapEntry::Occupied(entry) if *entry.get() >= next_value => {
+                        stats.pruned += 1;
+                        continue;
+                    }
+                    MapEntry::Occupied(mut entry) => {
+                        entry.insert(next_value);
+                    }
+                    MapEntry::Vacant(entry) => {
+                        entry.insert(next_value);
+                    }
+                }
+
+                let bound = upper_bound(&next);
+
+                if bound <= best_value {
+                    stats.pruned += 1;
+                    continue;
+                }
+
+                frontier.push(Candidate {
+                    bound,
+                    value: next_value,
+                    key: next_key,
+                    state: next,
+                });
+            }
+        }
+
+        Outcome {
+            best,
+            value: best_value,
+            stats,
+            complete: true,
+        }
+    }
+
+    fn over_budget(&self, stats: &Stats, started: Instant) -> bool {
+        self.node_budget.is_some_and(|nodes| stats.explored >= nodes)
+            || self.time_budget.is_some_and(|time| started.elapsed() >= time)
+    }
+}
+
+struct Candidate<S, K, V> {
+    bound: V,
+    value: V,
+    key: K,
+    state: S,
+}
+
+impl<S, K, V: Ord> PartialEq for Candidate<S, K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bound == other.bound
+    }
+}
+
+impl<S, K, V: Ord> Eq for Candidate<S, K, V> {}
+
+impl<S, K, V: Ord> PartialOrd for Candidate<S, K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders candidates by their upper bound only
+impl<S, K, V: Ord> Ord for Candidate<S, K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bound.cmp(&other.bound)
+    }
+}
+
+enum Frontier<S, K, V> {
+    Stack(Vec<Candidate<S, K, V>>),
+    Heap(BinaryHeap<Candidate<S, K, V>>),
+}
+
+impl<S, K, V: Ord> Frontier<S, K, V> {
+    fn new(strategy: Strategy) -> Self {
+        match strategy {
+            Strategy::DepthFirst => Self::Stack(Vec::new()),
+            Strategy::BestFirst => Self::Heap(BinaryHeap::new()),
+        }
+    }
+
+    fn push(&mut self, candidate: Candidate<S, K, V>) {
+        match self {
+            Self::Stack(stack) => stack.push(candidate),
+            Self::Heap(heap) => heap.push(candidate),
+        }
+    }
+
+    fn pop(&mut self) -> Option<Candidate<S, K, V>> {
+        match self {
+            Self::Stack(stack) => stack.pop(),
+            Self::Heap(heap) => heap.pop(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAPACITY: u32 = 20;
+    /// `(weight, value)`
+    const ITEMS: [(u32, u32); 8] = [(5, 10), (4, 40), (6, 30), (3, 50), (7, 35), (2, 8), (9, 60), (4, 20)];
+
+    /// The next item to decide on, the weight and value so far, and the items taken
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Knapsack {
+        next: usize,
+        weight: u32,
+        value: u32,
+        taken: u8,
+    }
+
+    fn successors(state: &Knapsack) -> Vec<Knapsack> {
+        let Some(&(weight, value)) = ITEMS.get(state.next) else {
+            return Vec::new();
+        };
+        let skip = Knapsack {
+            next: state.next + 1,
+            ..*state
+        };
+        let take = Knapsack {
+            next: state.next + 1,
+            weight: state.weight + weight,
+            value: state.value + value,
+            taken: state.taken | 1 << state.next,
+        };
+
+        if take.weight <= CAPACITY {
+            vec![skip, take]
+        } else {
+            vec![skip]
+        }
+    }
+
+    /// Takes every remaining item, ignoring the capacity
+    fn loose_bound(state: &Knapsack) -> u32 {
+        state.value + ITEMS[state.next..].iter().map(|&(_, value)| value).sum::<u32>()
+    }
+
+    fn brute_force() -> u32 {
+        (0..1u32 << ITEMS.len())
+            .map(|taken| {
+                let chosen = ITEMS.iter().enumerate().filter(|&(i, _)| taken & 1 << i != 0);
+
+                chosen.fold((0, 0), |(weight, value), (_, item)| (weight + item.0, value + item.1))
+            })
+            .filter(|&(weight, _)| weight <= CAPACITY)
+            .map(|(_, value)| value)
+            .max()
+            .unwrap()
+    }
+
+    fn solve(search: BranchAndBound, upper_bound: impl FnMut(&Knapsack) -> u32) -> Outcome<Knapsack, u32> {
+        let start = Knapsack {
+            next: 0,
+            weight: 0,
+            value: 0,
+            taken: 0,
+        };
+
+        search.maximize(start, successors, |state| state.value, upper_bound, |state| (state.next, state.weight))
+    }
+
+    #[test]
+    fn finds_the_optimum() {
+        let optimum = brute_force();
+
+        assert_eq!(optimum, 170);
+
+        for strategy in [Strategy::DepthFirst, Strategy::BestFirst] {
+            let outcome = solve(BranchAndBound::new().strategy(strategy), loose_bound);
+            let taken = ITEMS.iter().enumerate().filter(|&(i, _)| outcome.best.taken & 1 << i != 0);
+
+            assert_eq!(outcome.value, optimum, "{:?}", strategy);
+            assert_eq!(taken.map(|(_, &(_, value))| value).sum::<u32>(), optimum);
+            assert!(outcome.best.weight <= CAPACITY);
+            assert!(outcome.complete);
+        }
+    }
+
+    #[test]
+    fn counts_every_state_without_pruning() {
+        // Distinct keys for every state and a useless bound leave nothing to prune
+        let outcome = BranchAndBound::new().maximize(
+            1u32,
+            |&path: &u32| if path < 1 << 4 { vec![path * 2, path * 2 + 1] } else { Vec::new() },
+            |&path| path,
+            |_| u32::MAX,
+            |&path| path,
+        );
+
+        assert_eq!(outcome.value, 31);
+        assert_eq!(outcome.stats, Stats { explored: 31, pruned: 0 });
+    }
+
+    #[test]
+    fn pruning_keeps_the_optimum() {
+        let unbounded = solve(BranchAndBound::new(), |_| u32::MAX);
+        let loose = solve(BranchAndBound::new(), loose_bound);
+        // Tight from the start, so nothing is explored once an optimal state is found
+        let exact = solve(BranchAndBound::new(), |_| brute_force());
+
+        assert_eq!(unbounded.value, brute_force());
+        assert_eq!(loose.value, brute_force());
+        assert_eq!(exact.value, brute_force());
+        assert!(loose.stats.explored < unbounded.stats.explored);
+        assert!(exact.stats.explored < unbounded.stats.explored);
+        assert!(loose.stats.pruned > 0 && exact.stats.pruned > 0);
+    }
+
+    #[test]
+    fn node_budget_stops_early() {
+        for strategy in [Strategy::DepthFirst, Strategy::BestFirst] {
+            let outcome = solve(BranchAndBound::new().strategy(strategy).node_budget(3), loose_bound);
+
+            assert!(!outcome.complete);
+            assert_eq!(outcome.stats.explored, 3);
+            assert!(outcome.value <= brute_force());
+        }
+    }
+
+    #[test]
+    fn time_budget_stops_early() {
+        let outcome = solve(BranchAndBound::new().time_budget(Duration::ZERO), loose_bound);
+
+        assert!(!outcome.complete);
+        assert_eq!(outcome.stats.explored, 0);
+        assert_eq!(outcome.value, 0);
+    }
+}
@@ -0,0 +1,201 @@
+use std::{
+    error::Error,
+    fmt,
+    ops::Range,
+};
+
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    text: String,
+    expected: &'static str,
+    reason: Option<String>,
+    span: Range<usize>,
+    location: Option<Location>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl ParseError {
+    pub fn new(text: &str, expected: &'static str) -> Self {
+        Self {
+            text: text.into(),
+            expected,
+            reason: None,
+            span: span(text),
+            location: None,
+        }
+    }
+
+    pub fn with_reason(mut self, reason: impl fmt::Display) -> Self {
+        self.reason = Some(reason.to_string());
+        self
+    }
+
+    /// Resolves the line and column of the offending text,
+    /// provided it was sliced out of `input`.
+    pub fn locate(mut self, input: &str) -> Self {
+        if !within(&self.span, input) {
+            return self;
+        }
+
+        let preceding = &input[..self.span.start - span(input).start];
+        let line_start = preceding.rfind('\n').map_or(0, |i| i + 1);
+
+        self.location = Some(Location {
+            line: preceding.matches('\n').count() + 1,
+            column: preceding[line_start..].chars().count() + 1,
+        });
+
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn expected(&self) -> &'static str {
+        self.expected
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    pub fn location(&self) -> Option<Location> {
+        self.location
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(location) = self.location {
+            write!(f, "{location}: ")?;
+        }
+
+        write!(f, "expected {}, found {:?}", self.expected, self.text)?;
+
+        if let Some(reason) = &self.reason {
+            write!(f, " ({reason})")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// Parses every line of `input`, failing with the first error, located within `input`.
+///
+/// Errors about text that wasn't sliced out of the line are located at the start of the line.
+pub fn try_parse_lines<'a, T>(
+    input: &'a str,
+    mut parse: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            parse(line).map_err(|mut err| {
+                if !within(&err.span, input) {
+                    err.span = span(&line[..0]);
+                }
+
+                err.locate(input)
+            })
+        })
+        .collect()
+}
+
+/// Like `try_parse_lines`, but panics with the located error
+pub fn parse_lines<'a, T>(input: &'a str, parse: impl FnMut(&'a str) -> Result<T, ParseError>) -> Vec<T> {
+    try_parse_lines(input, parse).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// The addresses of the text, which identify it within the string it was sliced out of
+fn span(text: &str) -> Range<usize> {
+    let start = text.as_ptr() as usize;
+
+    start..start + text.len()
+}
+
+fn within(span: &Range<usize>, input: &str) -> bool {
+    let input = self::span(input);
+
+    span.start >= input.start && span.end <= input.end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StrExt;
+
+    #[test]
+    fn locate_finds_line_and_column() {
+        let input = "1 2\n3 x 5\n";
+        let err = input.lines().nth(1).unwrap()[2..3].try_u32().unwrap_err().locate(input);
+
+        assert_eq!(err.location(), Some(Location { line: 2, column: 3 }));
+        assert_eq!(err.to_string(), r#"line 2, column 3: expected u32, found "x" (invalid digit found in string)"#);
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        let input = "äöü\n→ ß=x";
+        let field = &input[input.find('x').unwrap()..];
+        let err = ParseError::new(field, "a number").locate(input);
+
+        assert_eq!(err.location(), Some(Location { line: 2, column: 5 }));
+    }
+
+    #[test]
+    fn foreign_text_is_not_located() {
+        let err = ParseError::new("x", "a number").locate("1\n2\nx");
+
+        assert_eq!(err.location(), None);
+        assert_eq!(err.to_string(), r#"expected a number, found "x""#);
+    }
+
+    #[test]
+    fn parse_lines_locates_errors() {
+        let input = "1,2\n3,4\n5,é6\n";
+        let parse = |line: &str| {
+            let (a, b) = line.split_once(',').unwrap();
+
+            Ok((a.try_u32()?, b.try_u32()?))
+        };
+
+        assert_eq!(try_parse_lines("1,2\n3,4", parse).unwrap(), [(1, 2), (3, 4)]);
+
+        let err = try_parse_lines(input, parse).unwrap_err();
+
+        assert_eq!(err.text(), "é6");
+        assert_eq!(err.location(), Some(Location { line: 3, column: 3 }));
+    }
+
+    #[test]
+    fn parse_lines_falls_back_to_the_line() {
+        let err = try_parse_lines("ok\nbad", |line| match line {
+            "ok" => Ok(()),
+            _ => Err(ParseError::new("something else", "ok")),
+        })
+        .unwrap_err();
+
+        assert_eq!(err.location(), Some(Location { line: 2, column: 1 }));
+        assert_eq!(err.text(), "something else");
+    }
+
+    #[test]
+    #[should_panic(expected = "line 2, column 1: expected u32")]
+    fn parse_lines_panics_with_the_location() {
+        parse_lines("1\nx", |line| line.try_u32());
+    }
+}
@@ -0,0 +1,402 @@
+use std::{
+    convert::TryFrom,
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+use crate::ParseError;
+
+/// Balanced base 5, written with the digits `=-012`
+pub type Snafu = Balanced<5>;
+
+/// An integer in a balanced base, whose digits range from `-(BASE / 2)` to `BASE / 2`.
+///
+/// Any odd base from 3 to 255 supports conversions and arithmetic. The digits -2 and -1
+/// are written as `=` and `-`, so parsing and `Display` are limited to bases 3 and 5.
+#[derive(Default, Clone, PartialEq, Eq, Hash)]
+pub struct Balanced<const BASE: u32> {
+    /// Least significant first, without trailing zeros
+    digits: Vec<i8>,
+}
+
+impl<const BASE: u32> Balanced<BASE> {
+    const HALF: i8 = {
+        assert!(BASE % 2 == 1 && BASE >= 3 && BASE <= 255, "balanced bases must be odd and within 3..=255");
+        (BASE / 2) as i8
+    };
+
+    /// Checks that the text form can write every digit
+    const TEXT: () = assert!(BASE == 3 || BASE == 5, "the text form is limited to balanced bases 3 and 5");
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    /// Builds a number from its digits, least significant first.
+    /// Returns `None` if any digit is out of range.
+    pub fn from_digits(digits: impl IntoIterator<Item = i8>) -> Option<Self> {
+        let digits = digits.into_iter().collect::<Vec<_>>();
+
+        if digits.iter().any(|digit| digit.abs() > Self::HALF) {
+            return None;
+        }
+
+        Some(Self::normalized(digits))
+    }
+
+    /// The digits, least significant first
+    pub fn digits(&self) -> &[i8] {
+        &self.digits
+    }
+
+    pub fn from_i128(mut value: i128) -> Self {
+        let base = BASE as i128;
+        let mut digits = Vec::new();
+
+        while value != 0 {
+            let mut digit = value.rem_euclid(base);
+            let mut quotient = value.div_euclid(base);
+
+            if digit > Self::HALF as i128 {
+                digit -= base;
+                quotient += 1;
+            }
+
+            digits.push(digit as i8);
+            value = quotient;
+        }
+
+        Self { digits }
+    }
+
+    /// The value as an `i128`, if it fits
+    pub fn to_i128(&self) -> Option<i128> {
+        let base = BASE as i128;
+        // Every prefix of the digits has the sign of the leading digit, so the magnitude
+        // can be accumulated as a negative number, whose range also covers `i128::MIN`
+        let sign = self.digits.last().map_or(0, |&digit| digit.signum() as i128);
+        let negated = self.digits.iter().rev().try_fold(0i128, |negated, &digit| {
+            let digit = -sign * digit as i128;
+
+            // `negated * base + digit`, arranged so the product can't overflow when the sum fits
+            (negated + 1).checked_mul(base)?.checked_add(digit - base)
+        })?;
+
+        if sign < 0 {
+            Some(negated)
+        } else {
+            negated.checked_neg()
+        }
+    }
+
+    /// The value as an `i64`, if it fits
+    pub fn to_i64(&self) -> Option<i64> {
+        i64::try_from(self.to_i128()?).ok()
+    }
+
+    fn write_digits(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.digits.is_empty() {
+            return f.write_str("0");
+        }
+
+        for &digit in self.digits.iter().rev() {
+            let ch = match digit {
+                -2 => '=',
+                -1 => '-',
+                _ => (b'0' + digit as u8) as char,
+            };
+
+            write!(f, "{}", ch)?;
+        }
+
+        Ok(())
+    }
+
+    fn normalized(mut digits: Vec<i8>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        Self { digits }
+    }
+}
+
+impl<const BASE: u32> From<i64> for Balanced<BASE> {
+    fn from(value: i64) -> Self {
+        Self::from_i128(value.into())
+    }
+}
+
+impl<const BASE: u32> From<i128> for Balanced<BASE> {
+    fn from(value: i128) -> Self {
+        Self::from_i128(value)
+    }
+}
+
+/// Adds digit by digit, carrying whenever a digit sum leaves the balanced range
+impl<const BASE: u32> Add<&Balanced<BASE>> for &Balanced<BASE> {
+    type Output = Balanced<BASE>;
+
+    fn add(self, rhs: &Balanced<BASE>) -> Balanced<BASE> {
+        // Digit sums exceed `i8` in large bases
+        let half = Balanced::<BASE>::HALF as i16;
+        let base = BASE as i16;
+        let len = self.digits.len().max(rhs.digits.len());
+        let mut digits = Vec::with_capacity(len + 1);
+        let mut carry = 0;
+
+        for i in 0..len {
+            let lhs = self.digits.get(i).copied().unwrap_or(0);
+            let rhs = rhs.digits.get(i).copied().unwrap_or(0);
+            let mut digit = lhs as i16 + rhs as i16 + carry;
+
+            carry = 0;
+
+            if digit > half {
+                digit -= base;
+                carry = 1;
+            } else if digit < -half {
+                digit += base;
+                carry = -1;
+            }
+
+            digits.push(digit as i8);
+        }
+
+        digits.push(carry as i8);
+
+        Balanced::normalized(digits)
+    }
+}
+
+impl<const BASE: u32> Add for Balanced<BASE> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        &self + &rhs
+    }
+}
+
+impl<const BASE: u32> AddAssign<&Balanced<BASE>> for Balanced<BASE> {
+    fn add_assign(&mut self, rhs: &Self) {
+        *self = &*self + rhs;
+    }
+}
+
+impl<const BASE: u32> AddAssign for Balanced<BASE> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self += &rhs;
+    }
+}
+
+/// Negates every digit, which is all it takes in a balanced base
+impl<const BASE: u32> Neg for Balanced<BASE> {
+    type Output = Self;
+
+    fn neg(mut self) -> Self {
+        for digit in &mut self.digits {
+            *digit = -*digit;
+        }
+
+        self
+    }
+}
+
+impl<const BASE: u32> Sub for Balanced<BASE> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<const BASE: u32> SubAssign for Balanced<BASE> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self += -rhs;
+    }
+}
+
+impl<const BASE: u32> Sum for Balanced<BASE> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |sum, number| sum + number)
+    }
+}
+
+impl<'a, const BASE: u32> Sum<&'a Balanced<BASE>> for Balanced<BASE> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |mut sum, number| {
+            sum += number;
+            sum
+        })
+    }
+}
+
+impl<const BASE: u32> FromStr for Balanced<BASE> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::TEXT;
+
+        if s.is_empty() {
+            return Err(ParseError::new(s, "balanced digits"));
+        }
+
+        let digits = s
+            .chars()
+            .rev()
+            .map(|ch| match ch {
+                '=' => Some(-2),
+                '-' => Some(-1),
+                _ => ch.to_digit(10).map(|digit| digit as i8),
+            })
+            .collect::<Option<Vec<_>>>();
+
+        digits
+            .and_then(Self::from_digits)
+            .ok_or_else(|| ParseError::new(s, "balanced digits").with_reason(format_args!("base {}", BASE)))
+    }
+}
+
+impl<const BASE: u32> fmt::Display for Balanced<BASE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::TEXT;
+
+        self.write_digits(f)
+    }
+}
+
+/// Bases without a text form list their digits, most significant first
+impl<const BASE: u32> fmt::Debug for Balanced<BASE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if BASE <= 5 {
+            self.write_digits(f)?;
+        } else {
+            f.debug_list().entries(self.digits.iter().rev()).finish()?;
+        }
+
+        write!(f, " (balanced base {})", BASE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EDGES: [i128; 7] = [0, 1, -1, i128::MAX, i128::MIN, i128::MAX - 1, i128::MIN + 1];
+
+    #[test]
+    fn snafu_examples() {
+        let examples = [(1i64, "1"), (3, "1="), (2022, "1=11-2"), (12345, "1-0---0"), (314159265, "1121-1110-1=0")];
+
+        for (value, text) in examples {
+            assert_eq!(Snafu::from(value).to_string(), text);
+            assert_eq!(text.parse::<Snafu>().unwrap().to_i64(), Some(value));
+        }
+    }
+
+    #[test]
+    fn edge_values_round_trip() {
+        for value in EDGES {
+            assert_eq!(Snafu::from_i128(value).to_i128(), Some(value));
+            assert_eq!(Balanced::<3>::from_i128(value).to_i128(), Some(value));
+        }
+
+        assert_eq!(Snafu::zero().to_string(), "0");
+        assert!(Snafu::zero().digits().is_empty());
+    }
+
+    #[test]
+    fn out_of_range_values_are_none() {
+        let one = Snafu::from_i128(1);
+
+        assert_eq!((Snafu::from_i128(i128::MAX) + one.clone()).to_i128(), None);
+        assert_eq!((Snafu::from_i128(i128::MIN) - one).to_i128(), None);
+
+        // Multiplying by the base shifts the digits
+        let shifted = |value: i128| {
+            let digits = Snafu::from_i128(value).digits().to_vec();
+
+            Snafu::from_digits(Some(0).into_iter().chain(digits)).unwrap()
+        };
+
+        assert_eq!(shifted(i128::MAX / 2).to_i128(), None);
+        assert_eq!(shifted(i128::MIN / 2).to_i128(), None);
+        assert_eq!(shifted(i128::MAX / 5).to_i128(), Some(i128::MAX / 5 * 5));
+        assert_eq!(Snafu::from_i128(i128::MAX).to_i64(), None);
+    }
+
+    #[test]
+    fn invalid_digits() {
+        assert!("".parse::<Snafu>().is_err());
+        assert!("13".parse::<Snafu>().is_err());
+        assert!("3".parse::<Balanced<5>>().is_err());
+        assert!("2".parse::<Balanced<3>>().is_err());
+        assert_eq!(Snafu::from_digits([3]), None);
+    }
+
+    #[test]
+    fn other_odd_bases() {
+        let number = Balanced::<7>::from_i128(-100);
+
+        // -100 = -2 * 49 + 0 * 7 + -2
+        assert_eq!(number.digits(), [-2, 0, -2]);
+        assert_eq!(format!("{:?}", number), "[-2, 0, -2] (balanced base 7)");
+        assert_eq!(Balanced::<7>::from_digits([3, -3]).unwrap().to_i64(), Some(-18));
+        assert_eq!(Balanced::<7>::from_digits([4]), None);
+
+        for value in EDGES {
+            assert_eq!(Balanced::<255>::from_i128(value).to_i128(), Some(value));
+        }
+
+        let max_digits = Balanced::<255>::from_digits([127; 3]).unwrap();
+
+        assert_eq!((max_digits.clone() + max_digits).to_i64(), Some(2 * 127 * (1 + 255 + 255 * 255)));
+    }
+
+    proptest! {
+        #[test]
+        fn i128_round_trip(value: i128) {
+            prop_assert_eq!(Snafu::from_i128(value).to_i128(), Some(value));
+            prop_assert_eq!(Balanced::<3>::from_i128(value).to_i128(), Some(value));
+            prop_assert_eq!(Balanced::<7>::from_i128(value).to_i128(), Some(value));
+            prop_assert_eq!(Balanced::<255>::from_i128(value).to_i128(), Some(value));
+        }
+
+        #[test]
+        fn large_bases_add_like_i128(lhs: i64, rhs: i64) {
+            let (lhs, rhs) = (lhs as i128, rhs as i128);
+            let sum = Balanced::<255>::from_i128(lhs) + Balanced::<255>::from_i128(rhs);
+            let difference = Balanced::<9>::from_i128(lhs) - Balanced::<9>::from_i128(rhs);
+
+            prop_assert_eq!(sum.to_i128(), Some(lhs + rhs));
+            prop_assert_eq!(difference.to_i128(), Some(lhs - rhs));
+        }
+
+        #[test]
+        fn text_round_trip(value: i128) {
+            let number = Snafu::from_i128(value);
+
+            prop_assert_eq!(number.to_string().parse::<Snafu>().unwrap(), number);
+        }
+
+        #[test]
+        fn add_matches_i128(lhs: i64, rhs: i64) {
+            let (lhs, rhs) = (lhs as i128, rhs as i128);
+            let sum = Snafu::from_i128(lhs) + Snafu::from_i128(rhs);
+
+            prop_assert_eq!(sum.to_i128(), Some(lhs + rhs));
+            prop_assert_eq!((Snafu::from_i128(lhs) - Snafu::from_i128(rhs)).to_i128(), Some(lhs - rhs));
+        }
+
+        #[test]
+        fn add_near_the_edges(lhs: i128, rhs: i128) {
+            let sum = Snafu::from_i128(lhs) + Snafu::from_i128(rhs);
+
+            prop_assert_eq!(sum.to_i128(), lhs.checked_add(rhs));
+        }
+    }
+}
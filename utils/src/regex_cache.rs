@@ -0,0 +1,333 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+const NUM_SHARDS: usize = 16;
+const UNBOUNDED: usize = usize::MAX;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct RegexCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub len: usize,
+}
+
+pub fn regex_cache_stats() -> RegexCacheStats {
+    REGEX_CACHE.stats()
+}
+
+/// Limits the number of cached regexes. Once the limit is reached, regexes are evicted
+/// to make room, oldest first within each of the cache's shards (per-shard FIFO).
+/// That isn't necessarily the oldest regex overall. `None` removes the limit.
+pub fn set_regex_cache_capacity(capacity: Option<usize>) {
+    REGEX_CACHE.set_capacity(capacity);
+}
+
+pub(crate) fn with_cached_regex<F, R>(regex: &str, f: F) -> R
+where
+    F: FnOnce(&Regex) -> R,
+{
+    REGEX_CACHE.with(regex, f)
+}
+
+lazy_static! {
+    static ref REGEX_CACHE: RegexCache = RegexCache::new();
+}
+
+struct RegexCache {
+    shards: Vec<RwLock<Shard>>,
+    capacity: AtomicUsize,
+    /// The number of cached regexes, plus the slots reserved for regexes about to be inserted
+    len: AtomicUsize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+#[derive(Default)]
+struct Shard {
+    regexes: HashMap<String, Arc<Regex>>,
+    insertion_order: VecDeque<String>,
+}
+
+impl RegexCache {
+    fn new() -> Self {
+        Self {
+            shards: (0..NUM_SHARDS).map(|_| RwLock::default()).collect(),
+            capacity: AtomicUsize::new(UNBOUNDED),
+            len: AtomicUsize::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    fn stats(&self) -> RegexCacheStats {
+        RegexCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            len: self.len.load(Ordering::Relaxed),
+        }
+    }
+
+    fn set_capacity(&self, capacity: Option<usize>) {
+        self.capacity.store(capacity.unwrap_or(UNBOUNDED), Ordering::Relaxed);
+    }
+
+    fn with<F, R>(&self, pattern: &str, f: F) -> R
+    where
+        F: FnOnce(&Regex) -> R,
+    {
+        // The shard is unlocked before calling `f`, which may use the cache itself
+        let regex = match self.get(pattern) {
+            Some(regex) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                regex
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                self.insert(pattern)
+            }
+        };
+
+        f(&regex)
+    }
+
+    fn get(&self, pattern: &str) -> Option<Arc<Regex>> {
+        self.shard(pattern).read().unwrap().regexes.get(pattern).cloned()
+    }
+
+    fn insert(&self, pattern: &str) -> Arc<Regex> {
+        let shard_index = self.shard_index(pattern);
+
+        // Compile outside of the lock so other patterns in this shard stay available
+        let compiled_regex = Arc::new(Regex::new(pattern).unwrap());
+        let capacity = self.capacity.load(Ordering::Relaxed);
+
+        // Another thread may have cached the pattern while it was compiled, which needs no room
+        if let Some(regex) = self.get(pattern) {
+            return regex;
+        }
+
+        // Reserving a slot before inserting keeps concurrent misses from exceeding the capacity.
+        // If nothing is left to evict, the other slots are reserved, so the regex is not cached.
+        while !self.reserve_slot(capacity) {
+            if !self.evict_one(shard_index) {
+                return compiled_regex;
+            }
+        }
+
+        let mut shard = self.shards[shard_index].write().unwrap();
+
+        if let Some(regex) = shard.regexes.get(pattern) {
+            self.len.fetch_sub(1, Ordering::Relaxed);
+            return regex.clone();
+        }
+
+        shard.regexes.insert(pattern.into(), compiled_regex.clone());
+        shard.insertion_order.push_back(pattern.into());
+
+        compiled_regex
+    }
+
+    fn reserve_slot(&self, capacity: usize) -> bool {
+        self.len
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |len| (len < capacity).then_some(len + 1))
+            .is_ok()
+    }
+
+    /// Evicts the oldest regex of the first non-empty shard, starting at `shard_index`.
+    /// Only one shard is locked at a time.
+    fn evict_one(&self, shard_index: usize) -> bool {
+        for offset in 0..NUM_SHARDS {
+            let mut shard = self.shards[(shard_index + offset) % NUM_SHARDS].write().unwrap();
+
+            if let Some(oldest) = shard.insertion_order.pop_front() {
+                shard.regexes.remove(&oldest);
+                self.len.fetch_sub(1, Ordering::Relaxed);
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn shard(&self, pattern: &str) -> &RwLock<Shard> {
+        &self.shards[self.shard_index(pattern)]
+    }
+
+    fn shard_index(&self, pattern: &str) -> usize {
+        let mut hasher = DefaultHasher::new();
+        pattern.hash(&mut hasher);
+
+        hasher.finish() as usize % NUM_SHARDS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn cached_regexes(cache: &RegexCache) -> usize {
+        cache.shards.iter().map(|shard| shard.read().unwrap().regexes.len()).sum()
+    }
+
+    #[test]
+    fn hits_and_misses() {
+        let cache = RegexCache::new();
+
+        assert!(cache.with(r"^\d+$", |regex| regex.is_match("123")));
+        assert!(!cache.with(r"^\d+$", |regex| regex.is_match("abc")));
+        assert!(cache.with(r"^[a-z]+$", |regex| regex.is_match("abc")));
+
+        assert_eq!(
+            cache.stats(),
+            RegexCacheStats {
+                hits: 1,
+                misses: 2,
+                evictions: 0,
+                len: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn evicts_to_stay_within_capacity() {
+        let cache = RegexCache::new();
+
+        cache.set_capacity(Some(2));
+
+        for i in 0..5 {
+            cache.with(&format!("a{{{}}}", i), |_| ());
+        }
+
+        let stats = cache.stats();
+
+        assert_eq!((stats.misses, stats.evictions, stats.len), (5, 3, 2));
+        assert_eq!(cached_regexes(&cache), 2);
+
+        // Lowering the capacity evicts on the next miss
+        cache.set_capacity(Some(1));
+        cache.with("b", |_| ());
+
+        assert_eq!(cache.stats().len, 1);
+        assert_eq!(cached_regexes(&cache), 1);
+    }
+
+    fn is_cached(cache: &RegexCache, pattern: &str) -> bool {
+        cache.get(pattern).is_some()
+    }
+
+    /// Patterns that all end up in the shard of `first`
+    fn same_shard(cache: &RegexCache, first: &str, count: usize) -> Vec<String> {
+        let shard_index = cache.shard_index(first);
+
+        (0..)
+            .map(|i| format!("{}{}", first, i))
+            .filter(|pattern| cache.shard_index(pattern) == shard_index)
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn evicts_the_oldest_regex_of_a_shard() {
+        let cache = RegexCache::new();
+        let patterns = same_shard(&cache, "a", 3);
+
+        cache.set_capacity(Some(2));
+
+        for pattern in &patterns {
+            cache.with(pattern, |_| ());
+        }
+
+        assert!(!is_cached(&cache, &patterns[0]));
+        assert!(is_cached(&cache, &patterns[1]));
+        assert!(is_cached(&cache, &patterns[2]));
+    }
+
+    #[test]
+    fn evicts_from_other_shards_when_its_own_is_empty() {
+        let cache = RegexCache::new();
+        let old = "old";
+        let new = (0..)
+            .map(|i| format!("new{}", i))
+            .find(|new| cache.shard_index(new) != cache.shard_index(old))
+            .unwrap();
+
+        cache.set_capacity(Some(1));
+        cache.with(old, |_| ());
+        cache.with(&new, |_| ());
+
+        assert!(!is_cached(&cache, old));
+        assert!(is_cached(&cache, &new));
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn zero_capacity_caches_nothing() {
+        let cache = RegexCache::new();
+
+        cache.set_capacity(Some(0));
+        cache.with("a", |_| ());
+        cache.with("a", |_| ());
+
+        assert_eq!(cache.stats().misses, 2);
+        assert_eq!(cache.stats().len, 0);
+    }
+
+    #[test]
+    fn capacity_holds_across_threads() {
+        let cache = RegexCache::new();
+
+        cache.set_capacity(Some(10));
+
+        thread::scope(|scope| {
+            for thread in 0..8 {
+                let cache = &cache;
+
+                scope.spawn(move || {
+                    for i in 0..200 {
+                        let pattern = format!("x{}", (thread * 7 + i) % 50);
+
+                        assert!(cache.with(&pattern, |regex| regex.is_match(&pattern)));
+                        assert!(cache.stats().len <= 10);
+                    }
+                });
+            }
+        });
+
+        let stats = cache.stats();
+
+        assert_eq!(stats.hits + stats.misses, 8 * 200);
+        assert_eq!(stats.len, cached_regexes(&cache));
+        assert!(stats.len <= 10);
+    }
+
+    #[test]
+    fn closure_may_use_the_cache() {
+        let cache = RegexCache::new();
+        let outer = "outer";
+        // A pattern in the same shard, whose insertion needs the write lock
+        let inner = (0..)
+            .map(|i| format!("inner{}", i))
+            .find(|inner| cache.shard_index(inner) == cache.shard_index(outer))
+            .unwrap();
+
+        cache.with(outer, |_| ());
+        cache.with(outer, |_| cache.with(&inner, |_| ()));
+
+        assert_eq!(cache.stats().len, 2);
+    }
+}
@@ -0,0 +1,399 @@
+use std::fmt::{self, Write as _};
+use crate::{
+    geom::Vec2,
+    grid::{Grid, SparseGrid},
+};
+
+/// Anything that can be looked at cell by cell
+pub trait Cells {
+    type Cell;
+
+    /// The inclusive corners of the area worth rendering
+    fn bounds(&self) -> Option<(Vec2<i64>, Vec2<i64>)>;
+    fn cell(&self, pos: Vec2<i64>) -> Option<&Self::Cell>;
+}
+
+impl<T> Cells for Grid<T> {
+    type Cell = T;
+
+    fn bounds(&self) -> Option<(Vec2<i64>, Vec2<i64>)> {
+        if self.width() == 0 || self.height() == 0 {
+            return None;
+        }
+
+        Some((Vec2::new(0, 0), Vec2::new(self.width() as i64 - 1, self.height() as i64 - 1)))
+    }
+
+    fn cell(&self, pos: Vec2<i64>) -> Option<&T> {
+        let pos = pos.try_cast::<usize>()?;
+
+        self.get((pos.x, pos.y))
+    }
+}
+
+impl<T> Cells for SparseGrid<T> {
+    type Cell = T;
+
+    fn bounds(&self) -> Option<(Vec2<i64>, Vec2<i64>)> {
+        SparseGrid::bounds(self)
+    }
+
+    fn cell(&self, pos: Vec2<i64>) -> Option<&T> {
+        self.get(pos)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Style {
+    pub color: Option<Color>,
+    pub bold: bool,
+    pub reverse: bool,
+}
+
+/// A character with an optional ANSI style
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StyledCell {
+    pub ch: char,
+    pub style: Style,
+}
+
+impl StyledCell {
+    pub fn new(ch: char) -> Self {
+        Self {
+            ch,
+            style: Style::default(),
+        }
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.style.color = Some(color);
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.style.bold = true;
+        self
+    }
+
+    pub fn reverse(mut self) -> Self {
+        self.style.reverse = true;
+        self
+    }
+}
+
+impl From<char> for StyledCell {
+    fn from(ch: char) -> Self {
+        Self::new(ch)
+    }
+}
+
+impl fmt::Display for StyledCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.style == Style::default() {
+            return f.write_char(self.ch);
+        }
+
+        let mut codes = Vec::new();
+
+        if self.style.bold {
+            codes.push(1);
+        }
+
+        if self.style.reverse {
+            codes.push(7);
+        }
+
+        if let Some(color) = self.style.color {
+            codes.push(30 + color as u8);
+        }
+
+        let codes = codes.iter().map(u8::to_string).collect::<Vec<_>>().join(";");
+
+        write!(f, "\x1b[{}m{}\x1b[0m", codes, self.ch)
+    }
+}
+
+/// Renders cells through a mapping closure, which receives `None` for unoccupied cells.
+/// The result is available through `Display`.
+pub struct Render<'a, C: ?Sized, F> {
+    cells: &'a C,
+    f: F,
+    viewport: Option<(Vec2<i64>, Vec2<i64>)>,
+    labels: bool,
+}
+
+impl<'a, C, F, S> Render<'a, C, F>
+where
+    C: Cells + ?Sized,
+    F: Fn(Option<&C::Cell>) -> S,
+    S: Into<StyledCell>,
+{
+    pub fn new(cells: &'a C, f: F) -> Self {
+        Self {
+            cells,
+            f,
+            viewport: None,
+            labels: false,
+        }
+    }
+
+    /// Only renders the cells between the inclusive corners `min` and `max`
+    pub fn viewport(mut self, min: Vec2<i64>, max: Vec2<i64>) -> Self {
+        self.viewport = Some((min, max));
+        self
+    }
+
+    /// Prints the x coordinates above and the y coordinates left of the cells
+    pub fn labels(mut self) -> Self {
+        self.labels = true;
+        self
+    }
+
+    fn write_column_labels(&self, f: &mut fmt::Formatter<'_>, min: Vec2<i64>, max: Vec2<i64>, indent: usize) -> fmt::Result {
+        let labels = (min.x..=max.x).map(|x| x.to_string()).collect::<Vec<_>>();
+        let label_height = labels.iter().map(String::len).max().unwrap_or(0);
+
+        for row in 0..label_height {
+            write!(f, "{:indent$} ", "", indent = indent)?;
+
+            for label in &labels {
+                // Right-align the labels so the last digits line up with the cells
+                let padding = label_height - label.len();
+                let ch = row.checked_sub(padding).and_then(|i| label.chars().nth(i)).unwrap_or(' ');
+
+                f.write_char(ch)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<C, F, S> fmt::Display for Render<'_, C, F>
+where
+    C: Cells + ?Sized,
+    F: Fn(Option<&C::Cell>) -> S,
+    S: Into<StyledCell>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((min, max)) = self.viewport.or_else(|| self.cells.bounds()) else {
+            return Ok(());
+        };
+
+        let label_width = [min.y, max.y].iter().map(|y| y.to_string().len()).max().unwrap_or(0);
+
+        if self.labels {
+            self.write_column_labels(f, min, max, label_width)?;
+        }
+
+        for y in min.y..=max.y {
+            if self.labels {
+                write!(f, "{:>width$} ", y, width = label_width)?;
+            }
+
+            for x in min.x..=max.x {
+                let cell = (self.f)(self.cells.cell(Vec2::new(x, y))).into();
+
+                write!(f, "{}", cell)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Places two pictures next to each other, marking lines that differ with `!`
+pub fn side_by_side(left: &str, right: &str) -> String {
+    let left_lines = left.lines().collect::<Vec<_>>();
+    let right_lines = right.lines().collect::<Vec<_>>();
+    let left_width = left_lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    let num_lines = left_lines.len().max(right_lines.len());
+    let mut out = String::new();
+
+    for i in 0..num_lines {
+        let left_line = left_lines.get(i).copied().unwrap_or("");
+        let right_line = right_lines.get(i).copied().unwrap_or("");
+        let marker = if left_line == right_line { ' ' } else { '!' };
+        let padding = left_width - left_line.chars().count();
+
+        writeln!(out, "{}{:padding$} {} {}", left_line, "", marker, right_line, padding = padding).unwrap();
+    }
+
+    out
+}
+
+/// Reprints `after`, highlighting every character that differs from `before`
+pub fn highlight_changes(before: &str, after: &str) -> String {
+    let before_lines = before.lines().collect::<Vec<_>>();
+    let mut out = String::new();
+
+    for (i, line) in after.lines().enumerate() {
+        let mut before_chars = before_lines.get(i).copied().unwrap_or("").chars();
+
+        for ch in line.chars() {
+            if before_chars.next() == Some(ch) {
+                out.push(ch);
+            } else {
+                write!(out, "{}", StyledCell::new(ch).color(Color::Red).reverse()).unwrap();
+            }
+        }
+
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Asserts that a rendered picture matches the expected one, showing both side by side otherwise.
+///
+/// The expected picture may be indented and surrounded by blank lines,
+/// which allows writing it as an indented multi-line string literal.
+/// The actual picture is compared as it is, apart from a final newline,
+/// so blank rows and columns at its edges count. So does whitespace at the end of lines.
+#[track_caller]
+pub fn assert_picture_eq(actual: &str, expected: &str) {
+    let expected = normalize_picture(expected);
+
+    if !actual.lines().eq(expected.lines()) {
+        panic!(
+            "pictures differ (expected left, actual right):\n{}",
+            side_by_side(&expected, actual),
+        );
+    }
+}
+
+/// Removes the blank lines around the picture and the indentation common to all of its lines
+fn normalize_picture(picture: &str) -> String {
+    let is_blank = |line: &&str| line.trim().is_empty();
+    let lines = picture.lines().collect::<Vec<_>>();
+    let start = lines.iter().position(|line| !is_blank(line)).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !is_blank(line)).map_or(start, |i| i + 1);
+    let lines = &lines[start..end];
+    let indent = lines
+        .iter()
+        .filter(|line| !is_blank(line))
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<bool> {
+        Grid::from_vec(3, vec![true, false, false, false, true, false])
+    }
+
+    #[test]
+    fn renders_cells() {
+        let grid = grid();
+        let render = Render::new(&grid, |cell| if cell == Some(&true) { '#' } else { '.' });
+
+        assert_eq!(render.to_string(), "#..\n.#.\n");
+    }
+
+    #[test]
+    fn viewport_and_labels() {
+        let mut grid = SparseGrid::new();
+
+        grid.insert(Vec2::new(-1, 9), 'a');
+        grid.insert(Vec2::new(10, 11), 'b');
+
+        let render = Render::new(&grid, |cell| cell.copied().unwrap_or('.'))
+            .viewport(Vec2::new(-1, 9), Vec2::new(10, 10))
+            .labels();
+
+        assert_picture_eq(
+            &render.to_string(),
+            "
+               -          1
+               101234567890
+             9 a...........
+            10 ............
+            ",
+        );
+    }
+
+    #[test]
+    fn styled_cells() {
+        let grid = grid();
+        let render = Render::new(&grid, |cell| match cell {
+            Some(true) => StyledCell::new('#').color(Color::Green).bold(),
+            _ => StyledCell::new('.'),
+        });
+
+        assert!(render.to_string().starts_with("\x1b[1;32m#\x1b[0m..\n"));
+    }
+
+    #[test]
+    fn empty_grids_render_nothing() {
+        let grid = SparseGrid::<char>::new();
+
+        assert_eq!(Render::new(&grid, |_| '.').to_string(), "");
+    }
+
+    #[test]
+    fn side_by_side_marks_differences() {
+        assert_eq!(side_by_side("ab\ncd", "ab\nce\nf"), "ab   ab\ncd ! ce\n   ! f\n");
+    }
+
+    #[test]
+    fn highlight_changes_marks_characters() {
+        let highlighted = highlight_changes("ab\ncd", "ab\nxd");
+
+        assert_eq!(highlighted, "ab\n\x1b[7;31mx\x1b[0md\n");
+    }
+
+    #[test]
+    fn pictures_may_be_indented() {
+        assert_picture_eq("#.\n.#\n", "\n    #.\n    .#\n  ");
+        assert_picture_eq(" #\n#.", "\n      #\n     #.\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "pictures differ")]
+    fn leading_blank_columns_count() {
+        assert_picture_eq("  #\n #.\n", "\n      #\n     #.\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "pictures differ")]
+    fn blank_rows_count() {
+        assert_picture_eq("\n#.\n.#\n", "#.\n.#");
+    }
+
+    #[test]
+    #[should_panic(expected = "pictures differ")]
+    fn trailing_cells_count() {
+        assert_picture_eq("# \n##\n", "#\n##");
+    }
+
+    #[test]
+    #[should_panic(expected = "pictures differ")]
+    fn different_pictures() {
+        assert_picture_eq("#.\n.#", ".#\n#.");
+    }
+}
@@ -0,0 +1,100 @@
+//! Runtime support for `scan!` and `try_scan!`.
+
+use std::{
+    any::type_name,
+    fmt::Display,
+    str::FromStr,
+};
+use crate::ParseError;
+
+pub fn scan<'a, const N: usize, T>(
+    format: &'static str,
+    text: &'a str,
+    literals: &[&str],
+    parse: impl FnOnce([&'a str; N]) -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    parse(split_fields(format, text, literals)?)
+}
+
+/// Splits `text` into the fields between `literals`.
+///
+/// `literals` holds the prefix, the separators between fields and the suffix,
+/// so it is always one longer than the number of fields.
+/// Any whitespace within a literal matches one or more whitespace characters.
+pub fn split_fields<'a, const N: usize>(
+    format: &'static str,
+    text: &'a str,
+    literals: &[&str],
+) -> Result<[&'a str; N], ParseError> {
+    assert_eq!(literals.len(), N + 1);
+
+    let mismatch = || ParseError::new(text, format);
+    let trimmed = text.trim();
+    let mut fields = [""; N];
+    let mut pos = match_literal(trimmed, 0, literals[0]).ok_or_else(mismatch)?;
+
+    for (i, field) in fields.iter_mut().enumerate() {
+        let literal = literals[i + 1];
+        let is_last = i + 1 == N;
+
+        let (end, next_pos) = (pos + 1..=trimmed.len())
+            .filter(|end| trimmed.is_char_boundary(*end))
+            .find_map(|end| {
+                let next_pos = match_literal(trimmed, end, literal)?;
+
+                (!is_last || next_pos == trimmed.len()).then_some((end, next_pos))
+            })
+            .ok_or_else(mismatch)?;
+
+        *field = trimmed[pos..end].trim();
+        pos = next_pos;
+    }
+
+    if pos != trimmed.len() {
+        return Err(mismatch());
+    }
+
+    Ok(fields)
+}
+
+pub fn parse_field<T: FromStr>(field: &str) -> Result<T, ParseError>
+where
+    T::Err: Display,
+{
+    field.parse()
+        .map_err(|err| ParseError::new(field, type_name::<T>()).with_reason(err))
+}
+
+/// Returns the position after `literal` if it matches `text` at `pos`.
+fn match_literal(text: &str, mut pos: usize, literal: &str) -> Option<usize> {
+    let mut literal = literal;
+
+    while !literal.is_empty() {
+        let rest = &text[pos..];
+        let literal_ws = literal.len() - literal.trim_start().len();
+
+        if literal_ws > 0 {
+            let text_ws = rest.len() - rest.trim_start().len();
+
+            if text_ws == 0 && pos != 0 && pos != text.len() {
+                return None;
+            }
+
+            pos += text_ws;
+            literal = &literal[literal_ws..];
+            continue;
+        }
+
+        let word_len = literal.find(char::is_whitespace).unwrap_or(literal.len());
+        let word = &literal[..word_len];
+
+        if !rest.starts_with(word) {
+            return None;
+        }
+
+        pos += word_len;
+        literal = &literal[word_len..];
+    }
+
+    Some(pos)
+}
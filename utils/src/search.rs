@@ -0,0 +1,450 @@
+//! Graph searches over implicit graphs, described by a neighbour closure.
+//!
+//! Every search accepts multiple start nodes. Searching backwards from a goal
+//! works the same way, with a closure that yields predecessors instead of successors.
+
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+use num_traits::Zero;
+
+/// A reached goal, along with the path to it if paths are tracked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<N, C> {
+    pub goal: N,
+    pub cost: C,
+    /// Every node from the start up to and including the goal
+    pub path: Option<Vec<N>>,
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Search {
+    track_paths: bool,
+}
+
+impl Search {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the predecessor of every visited node, so that `Found::path` is filled in
+    pub fn track_paths(mut self) -> Self {
+        self.track_paths = true;
+        self
+    }
+
+    /// Breadth-first search, where every edge costs 1
+    pub fn bfs<N, I>(
+        &self,
+        starts: impl IntoIterator<Item = N>,
+        mut neighbours: impl FnMut(&N) -> I,
+        mut is_goal: impl FnMut(&N) -> bool,
+    ) -> Option<Found<N, usize>>
+    where
+        N: Clone + Eq + Hash,
+        I: IntoIterator<Item = N>,
+    {
+        let mut nodes = Nodes::new(self.track_paths);
+        let mut queue = VecDeque::new();
+
+        for start in starts {
+            if let Some(index) = nodes.visit(start, 0, None) {
+                queue.push_back(index);
+            }
+        }
+
+        while let Some(index) = queue.pop_front() {
+            if is_goal(&nodes.node(index)) {
+                return Some(nodes.found(index));
+            }
+
+            let cost = nodes.cost(index) + 1;
+
+            for neighbour in neighbours(&nodes.node(index)) {
+                if let Some(neighbour) = nodes.visit(neighbour, cost, Some(index)) {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Dijkstra's algorithm over non-negative edge costs
+    pub fn dijkstra<N, C, I>(
+        &self,
+        starts: impl IntoIterator<Item = N>,
+        neighbours: impl FnMut(&N) -> I,
+        is_goal: impl FnMut(&N) -> bool,
+    ) -> Option<Found<N, C>>
+    where
+        N: Clone + Eq + Hash,
+        C: Copy + Ord + Add<Output = C> + Zero,
+        I: IntoIterator<Item = (N, C)>,
+    {
+        self.astar(starts, neighbours, |_| C::zero(), is_goal)
+    }
+
+    /// A* search. The heuristic must never overestimate the remaining cost.
+    pub fn astar<N, C, I>(
+        &self,
+        starts: impl IntoIterator<Item = N>,
+        mut neighbours: impl FnMut(&N) -> I,
+        mut heuristic: impl FnMut(&N) -> C,
+        mut is_goal: impl FnMut(&N) -> bool,
+    ) -> Option<Found<N, C>>
+    where
+        N: Clone + Eq + Hash,
+        C: Copy + Ord + Add<Output = C> + Zero,
+        I: IntoIterator<Item = (N, C)>,
+    {
+        let mut nodes = Nodes::new(self.track_paths);
+        let mut queue = BinaryHeap::new();
+
+        for start in starts {
+            let estimate = heuristic(&start);
+
+            if let Some(index) = nodes.relax(start, C::zero(), None) {
+                queue.push(Reverse((estimate, C::zero(), index)));
+            }
+        }
+
+        while let Some(Reverse((_, cost, index))) = queue.pop() {
+            // Skip stale queue entries of nodes that were reached more cheaply since
+            if cost > nodes.cost(index) {
+                continue;
+            }
+
+            let node = nodes.node(index);
+
+            if is_goal(&node) {
+                return Some(nodes.found(index));
+            }
+
+            for (neighbour, edge_cost) in neighbours(&node) {
+                let neighbour_cost = cost + edge_cost;
+                let estimate = neighbour_cost + heuristic(&neighbour);
+
+                if let Some(neighbour) = nodes.relax(neighbour, neighbour_cost, Some(index)) {
+                    queue.push(Reverse((estimate, neighbour_cost, neighbour)));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// The breadth-first distance of every node reachable from the starts
+    pub fn bfs_distances<N, I>(
+        &self,
+        starts: impl IntoIterator<Item = N>,
+        mut neighbours: impl FnMut(&N) -> I,
+    ) -> HashMap<N, usize>
+    where
+        N: Clone + Eq + Hash,
+        I: IntoIterator<Item = N>,
+    {
+        let mut distances = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        for start in starts {
+            if let Entry::Vacant(entry) = distances.entry(start.clone()) {
+                entry.insert(0);
+                queue.push_back((start, 0));
+            }
+        }
+
+        while let Some((node, distance)) = queue.pop_front() {
+            for neighbour in neighbours(&node) {
+                if let Entry::Vacant(entry) = distances.entry(neighbour.clone()) {
+                    entry.insert(distance + 1);
+                    queue.push_back((neighbour, distance + 1));
+                }
+            }
+        }
+
+        distances
+    }
+}
+
+pub fn bfs<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Found<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    Search::new().bfs(Some(start), neighbours, is_goal)
+}
+
+pub fn multi_source_bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Found<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    Search::new().bfs(starts, neighbours, is_goal)
+}
+
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Found<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Zero,
+    I: IntoIterator<Item = (N, C)>,
+{
+    Search::new().dijkstra(Some(start), neighbours, is_goal)
+}
+
+pub fn astar<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> C,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Found<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Zero,
+    I: IntoIterator<Item = (N, C)>,
+{
+    Search::new().astar(Some(start), neighbours, heuristic, is_goal)
+}
+
+/// Every node seen so far, addressed by the order in which it was first seen
+struct Nodes<N, C> {
+    nodes: Vec<N>,
+    costs: Vec<C>,
+    predecessors: Option<Vec<Option<usize>>>,
+    indices: HashMap<N, usize>,
+}
+
+impl<N, C> Nodes<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord,
+{
+    fn new(track_paths: bool) -> Self {
+        Self {
+            nodes: Vec::new(),
+            costs: Vec::new(),
+            predecessors: track_paths.then(Vec::new),
+            indices: HashMap::new(),
+        }
+    }
+
+    fn node(&self, index: usize) -> N {
+        self.nodes[index].clone()
+    }
+
+    fn cost(&self, index: usize) -> C {
+        self.costs[index]
+    }
+
+    /// Records a node that hasn't been seen before, returning its index
+    fn visit(&mut self, node: N, cost: C, predecessor: Option<usize>) -> Option<usize> {
+        if self.indices.contains_key(&node) {
+            return None;
+        }
+
+        Some(self.insert(node, cost, predecessor))
+    }
+
+    /// Records a node if it is new or was only reached more expensively before, returning its index
+    fn relax(&mut self, node: N, cost: C, predecessor: Option<usize>) -> Option<usize> {
+        let Some(&index) = self.indices.get(&node) else {
+            return Some(self.insert(node, cost, predecessor));
+        };
+
+        if cost >= self.costs[index] {
+            return None;
+        }
+
+        self.costs[index] = cost;
+
+        if let Some(predecessors) = &mut self.predecessors {
+            predecessors[index] = predecessor;
+        }
+
+        Some(index)
+    }
+
+    fn insert(&mut self, node: N, cost: C, predecessor: Option<usize>) -> usize {
+        let index = self.nodes.len();
+
+        self.indices.insert(node.clone(), index);
+        self.nodes.push(node);
+        self.costs.push(cost);
+
+        if let Some(predecessors) = &mut self.predecessors {
+            predecessors.push(predecessor);
+        }
+
+        index
+    }
+
+    fn found(&self, index: usize) -> Found<N, C> {
+        let path = self.predecessors.as_ref().map(|predecessors| {
+            let mut path = vec![self.node(index)];
+            let mut current = index;
+
+            while let Some(predecessor) = predecessors[current] {
+                path.push(self.node(predecessor));
+                current = predecessor;
+            }
+
+            path.reverse();
+            path
+        });
+
+        Found {
+            goal: self.node(index),
+            cost: self.cost(index),
+            path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    const MAZE: &str = "\
+S..#....
+.#.#.##.
+.#...#E.
+.####.#.
+......#.";
+
+    fn maze() -> Grid<char> {
+        Grid::parse(MAZE, |ch| ch)
+    }
+
+    fn open_neighbours(maze: &Grid<char>, pos: (usize, usize)) -> Vec<(usize, usize)> {
+        maze.neighbours4(pos).filter(|&next| maze[next] != '#').collect()
+    }
+
+    /// Checks that every step of a path moves to an open neighbour
+    fn assert_walkable(maze: &Grid<char>, path: &[(usize, usize)]) {
+        for step in path.windows(2) {
+            assert!(open_neighbours(maze, step[0]).contains(&step[1]), "{:?} is not a step", step);
+        }
+    }
+
+    #[test]
+    fn bfs_tracks_the_shortest_path() {
+        let maze = maze();
+        let start = maze.position(|&ch| ch == 'S').unwrap();
+        let found = Search::new()
+            .track_paths()
+            .bfs(Some(start), |&pos| open_neighbours(&maze, pos), |&pos| maze[pos] == 'E')
+            .unwrap();
+        let path = found.path.unwrap();
+
+        assert_eq!((found.goal, found.cost), ((6, 2), 14));
+        assert_eq!(path.len(), 15);
+        assert_eq!(path[0], start);
+        assert_eq!(path.last(), Some(&found.goal));
+        assert_walkable(&maze, &path);
+    }
+
+    #[test]
+    fn paths_are_only_tracked_on_request() {
+        let maze = maze();
+        let found = bfs((0, 0), |&pos| open_neighbours(&maze, pos), |&pos| maze[pos] == 'E').unwrap();
+
+        assert_eq!(found.cost, 14);
+        assert_eq!(found.path, None);
+    }
+
+    #[test]
+    fn multiple_starts() {
+        let maze = maze();
+        let found = Search::new()
+            .track_paths()
+            .bfs([(0, 0), (7, 4)], |&pos| open_neighbours(&maze, pos), |&pos| maze[pos] == 'E')
+            .unwrap();
+
+        assert_eq!(found.cost, 3);
+        assert_eq!(found.path.unwrap()[0], (7, 4));
+        assert_eq!(multi_source_bfs([(0, 0), (0, 0)], |&pos| open_neighbours(&maze, pos), |_| true).unwrap().cost, 0);
+    }
+
+    #[test]
+    fn unreachable_goals() {
+        let maze = maze();
+
+        assert_eq!(bfs((0, 0), |&pos| open_neighbours(&maze, pos), |&pos| pos == (3, 0)), None);
+        let weighted = |&pos: &(usize, usize)| open_neighbours(&maze, pos).into_iter().map(|pos| (pos, 1u32));
+
+        assert_eq!(dijkstra((0, 0), weighted, |_| false), None);
+    }
+
+    #[test]
+    fn astar_matches_bfs() {
+        let maze = maze();
+        let goal = (6, 2);
+        let manhattan = |&(x, y): &(usize, usize)| x.abs_diff(goal.0) + y.abs_diff(goal.1);
+        let found = Search::new()
+            .track_paths()
+            .astar(
+                Some((0, 0)),
+                |&pos| open_neighbours(&maze, pos).into_iter().map(|pos| (pos, 1)),
+                manhattan,
+                |&pos| pos == goal,
+            )
+            .unwrap();
+
+        assert_eq!(found.cost, 14);
+        assert_walkable(&maze, found.path.as_ref().unwrap());
+    }
+
+    #[test]
+    fn dijkstra_skips_stale_entries() {
+        // `b` is queued expensively first, then reached more cheaply through `c`
+        let edges = HashMap::from([
+            ('a', vec![('b', 10), ('c', 1)]),
+            ('c', vec![('b', 1)]),
+            ('b', vec![('z', 20)]),
+        ]);
+        let mut expanded = Vec::new();
+        let found = Search::new()
+            .track_paths()
+            .dijkstra(
+                Some('a'),
+                |&node| {
+                    expanded.push(node);
+                    edges.get(&node).cloned().unwrap_or_default()
+                },
+                |&node| node == 'z',
+            )
+            .unwrap();
+
+        assert_eq!(found.cost, 22);
+        assert_eq!(found.path, Some(vec!['a', 'c', 'b', 'z']));
+        assert_eq!(expanded, ['a', 'c', 'b']);
+    }
+
+    #[test]
+    fn bfs_distances() {
+        let maze = maze();
+        let distances = Search::new().bfs_distances(Some((0, 0)), |&pos| open_neighbours(&maze, pos));
+
+        assert_eq!(distances[&(0, 0)], 0);
+        assert_eq!(distances[&(6, 2)], 14);
+        assert_eq!(distances[&(5, 3)], 10);
+        assert!(!distances.contains_key(&(3, 0)));
+        assert_eq!(distances.len(), maze.iter().filter(|&(_, &ch)| ch != '#').count());
+    }
+}
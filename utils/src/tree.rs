@@ -0,0 +1,261 @@
+use std::{
+    collections::BTreeMap,
+    ops::{Index, IndexMut},
+};
+
+/// Identifies a node within the `Tree` it was created by
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+/// An arena-backed tree of named nodes, like the directories and files of a filesystem
+#[derive(Debug, Clone)]
+pub struct Tree<N> {
+    nodes: Vec<Node<N>>,
+}
+
+#[derive(Debug, Clone)]
+struct Node<N> {
+    name: String,
+    value: N,
+    parent: Option<NodeId>,
+    children: BTreeMap<String, NodeId>,
+}
+
+impl<N> Tree<N> {
+    /// A tree containing only an unnamed root
+    pub fn new(root: N) -> Self {
+        Self {
+            nodes: vec![Node {
+                name: String::new(),
+                value: root,
+                parent: None,
+                children: BTreeMap::new(),
+            }],
+        }
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    /// Adds a child to `parent`, replacing the value of an existing child with the same name
+    pub fn insert(&mut self, parent: NodeId, name: &str, value: N) -> NodeId {
+        if let Some(child) = self.child(parent, name) {
+            self.nodes[child.0].value = value;
+            return child;
+        }
+
+        let child = NodeId(self.nodes.len());
+
+        self.nodes.push(Node {
+            name: name.into(),
+            value,
+            parent: Some(parent),
+            children: BTreeMap::new(),
+        });
+        self.nodes[parent.0].children.insert(name.into(), child);
+
+        child
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.nodes[id.0].name
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].parent
+    }
+
+    pub fn child(&self, id: NodeId, name: &str) -> Option<NodeId> {
+        self.nodes[id.0].children.get(name).copied()
+    }
+
+    /// The children of a node, ordered by name
+    pub fn children(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes[id.0].children.values().copied()
+    }
+
+    pub fn is_leaf(&self, id: NodeId) -> bool {
+        self.nodes[id.0].children.is_empty()
+    }
+
+    /// The parent, grandparent and so on, up to and including the root
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut current = id;
+
+        std::iter::from_fn(move || {
+            current = self.parent(current)?;
+            Some(current)
+        })
+    }
+
+    /// The absolute path of a node, like `/a/b`
+    pub fn path(&self, id: NodeId) -> String {
+        if id == self.root() {
+            return "/".into();
+        }
+
+        let mut names = Some(id)
+            .into_iter()
+            .chain(self.ancestors(id))
+            .map(|id| self.name(id))
+            .collect::<Vec<_>>();
+        names.reverse();
+        names.join("/")
+    }
+
+    /// Follows a `/`-separated path, starting at the root if it is absolute and at `from` otherwise.
+    /// `.` stays put, and `..` moves to the parent, except at the root.
+    pub fn navigate(&self, from: NodeId, path: &str) -> Option<NodeId> {
+        let mut current = if path.starts_with('/') { self.root() } else { from };
+
+        for name in path.split('/') {
+            current = match name {
+                "" | "." => current,
+                ".." => self.parent(current).unwrap_or(current),
+                _ => self.child(current, name)?,
+            };
+        }
+
+        Some(current)
+    }
+
+    /// The subtree of `id`, visiting every node before its children
+    pub fn pre_order(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut stack = vec![id];
+
+        std::iter::from_fn(move || {
+            let id = stack.pop()?;
+            stack.extend(self.nodes[id.0].children.values().rev());
+            Some(id)
+        })
+    }
+
+    /// The subtree of `id`, visiting every node after its children
+    pub fn post_order(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut stack = vec![(id, false)];
+
+        std::iter::from_fn(move || loop {
+            let (id, expanded) = stack.pop()?;
+
+            if expanded {
+                return Some(id);
+            }
+
+            stack.push((id, true));
+            stack.extend(self.nodes[id.0].children.values().rev().map(|&child| (child, false)));
+        })
+    }
+
+    /// Folds the subtree of `id` bottom-up. `f` receives every node along with
+    /// the folded values of its children, ordered by name.
+    pub fn fold<T>(&self, id: NodeId, mut f: impl FnMut(NodeId, &N, &[T]) -> T) -> T {
+        let mut folded = Vec::new();
+
+        for id in self.post_order(id) {
+            let children_start = folded.len() - self.nodes[id.0].children.len();
+            let value = f(id, &self.nodes[id.0].value, &folded[children_start..]);
+
+            folded.truncate(children_start);
+            folded.push(value);
+        }
+
+        folded.pop().unwrap()
+    }
+}
+
+impl<N> Index<NodeId> for Tree<N> {
+    type Output = N;
+
+    fn index(&self, id: NodeId) -> &N {
+        &self.nodes[id.0].value
+    }
+}
+
+impl<N> IndexMut<NodeId> for Tree<N> {
+    fn index_mut(&mut self, id: NodeId) -> &mut N {
+        &mut self.nodes[id.0].value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The directories of the day 7 example, with the sizes of their files
+    fn filesystem() -> Tree<u64> {
+        let mut tree = Tree::new(14848514 + 8504156);
+        let root = tree.root();
+        let a = tree.insert(root, "a", 29116 + 2557 + 62596);
+        let e = tree.insert(a, "e", 584);
+        let d = tree.insert(root, "d", 4060174 + 8033020 + 5626152 + 7214296);
+
+        assert_eq!(tree.path(e), "/a/e");
+        assert_eq!(tree.path(d), "/d");
+
+        tree
+    }
+
+    fn names(tree: &Tree<u64>, ids: impl Iterator<Item = NodeId>) -> Vec<&str> {
+        ids.map(|id| tree.name(id)).collect()
+    }
+
+    #[test]
+    fn navigate() {
+        let tree = filesystem();
+        let root = tree.root();
+        let e = tree.navigate(root, "/a/e").unwrap();
+
+        assert_eq!(tree.name(e), "e");
+        assert_eq!(tree.navigate(e, ".."), tree.child(root, "a"));
+        assert_eq!(tree.navigate(e, "../../d"), tree.child(root, "d"));
+        assert_eq!(tree.navigate(e, "/d/"), tree.child(root, "d"));
+        assert_eq!(tree.navigate(e, "./."), Some(e));
+        assert_eq!(tree.navigate(e, "/"), Some(root));
+        assert_eq!(tree.navigate(root, "../.."), Some(root));
+        assert_eq!(tree.navigate(root, "a/x"), None);
+        assert_eq!(tree.navigate(e, "d"), None);
+    }
+
+    #[test]
+    fn insert_replaces_existing_children() {
+        let mut tree = filesystem();
+        let root = tree.root();
+        let a = tree.child(root, "a").unwrap();
+
+        assert_eq!(tree.insert(root, "a", 1), a);
+        assert_eq!(tree[a], 1);
+        assert_eq!(tree.children(root).count(), 2);
+        assert!(!tree.is_leaf(a));
+        assert!(tree.is_leaf(tree.child(a, "e").unwrap()));
+    }
+
+    #[test]
+    fn traversal_orders() {
+        let tree = filesystem();
+        let root = tree.root();
+        let e = tree.navigate(root, "/a/e").unwrap();
+
+        assert_eq!(names(&tree, tree.pre_order(root)), ["", "a", "e", "d"]);
+        assert_eq!(names(&tree, tree.post_order(root)), ["e", "a", "d", ""]);
+        assert_eq!(names(&tree, tree.ancestors(e)), ["a", ""]);
+        assert_eq!(names(&tree, tree.post_order(e)), ["e"]);
+    }
+
+    #[test]
+    fn fold() {
+        let tree = filesystem();
+        let root = tree.root();
+        let total_size = |_, &files: &u64, children: &[u64]| files + children.iter().sum::<u64>();
+
+        assert_eq!(tree.fold(root, total_size), 48381165);
+        assert_eq!(tree.fold(tree.navigate(root, "a").unwrap(), total_size), 94853);
+
+        // Children arrive ordered by name, each after its own subtree was folded
+        let rendered = tree.fold(root, |id, _, children: &[String]| {
+            format!("{}({})", tree.name(id), children.join(","))
+        });
+
+        assert_eq!(rendered, "(a(e()),d())");
+    }
+}
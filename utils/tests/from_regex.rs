@@ -0,0 +1,145 @@
+use std::str::FromStr;
+
+use utils::{FromRegex, ParseError};
+
+#[derive(Debug, PartialEq, FromRegex)]
+#[re(r"move (?P<amount>\d+) from (?P<from>\d+) to (?P<to>\d+)")]
+struct Instruction {
+    amount: usize,
+    from: usize,
+    to: usize,
+}
+
+#[derive(Debug, PartialEq)]
+enum Operand {
+    Old,
+    Number(u64),
+}
+
+impl FromStr for Operand {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        match s {
+            "old" => Ok(Self::Old),
+            _ => s.parse().map(Self::Number).map_err(|_| ParseError::new(s, "old or a number")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, FromRegex)]
+#[re(r"new = old (?P<op>[*+]) (?P<operand>\w+)")]
+struct Operation {
+    op: char,
+    operand: Operand,
+}
+
+#[derive(Debug, PartialEq, FromRegex)]
+#[re(r"Valve (?P<name>\w+)(?: has flow rate=(?P<rate>\d+))?; tunnels? leads? to valves? (?P<tunnels>.+)")]
+struct Valve {
+    name: String,
+    rate: Option<u32>,
+    #[re(sep = ", ")]
+    tunnels: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, FromRegex)]
+#[re(r"Starting items: (?P<items>.*)")]
+struct Items {
+    #[re(sep = ", ")]
+    items: Vec<u64>,
+}
+
+#[derive(Debug, PartialEq, FromRegex)]
+#[re(r"(?P<name>\w+)(?: -> (?P<children>.*))?")]
+struct Program {
+    name: String,
+    #[re(sep = ", ")]
+    children: Vec<String>,
+}
+
+#[test]
+fn plain_fields() {
+    assert_eq!(
+        "move 3 from 1 to 2".parse::<Instruction>().unwrap(),
+        Instruction { amount: 3, from: 1, to: 2 },
+    );
+}
+
+#[test]
+fn the_whole_text_must_match() {
+    assert!("move 1 from 2 to 3 garbage".parse::<Instruction>().is_err());
+    assert!("please move 1 from 2 to 3".parse::<Instruction>().is_err());
+
+    let err = "move 1 from 2".parse::<Instruction>().unwrap_err();
+
+    assert_eq!(err.text(), "move 1 from 2");
+}
+
+#[test]
+fn nested_from_str() {
+    assert_eq!(
+        "new = old * old".parse::<Operation>().unwrap(),
+        Operation { op: '*', operand: Operand::Old },
+    );
+    assert_eq!(
+        "new = old + 6".parse::<Operation>().unwrap(),
+        Operation { op: '+', operand: Operand::Number(6) },
+    );
+
+    let err = "new = old + x".parse::<Operation>().unwrap_err();
+
+    assert!(err.expected().ends_with("Operand"));
+    assert_eq!(err.reason(), Some(r#"expected old or a number, found "x""#));
+}
+
+#[test]
+fn optional_and_separated_fields() {
+    assert_eq!(
+        "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB".parse::<Valve>().unwrap(),
+        Valve {
+            name: "AA".into(),
+            rate: Some(0),
+            tunnels: vec!["DD".into(), "II".into(), "BB".into()],
+        },
+    );
+    assert_eq!(
+        "Valve HH; tunnel leads to valve GG".parse::<Valve>().unwrap(),
+        Valve {
+            name: "HH".into(),
+            rate: None,
+            tunnels: vec!["GG".into()],
+        },
+    );
+}
+
+#[test]
+fn separated_items_are_parsed() {
+    assert_eq!("Starting items: 79, 98".parse::<Items>().unwrap().items, [79, 98]);
+
+    let err = "Starting items: 79, x".parse::<Items>().unwrap_err();
+
+    assert_eq!(err.text(), "x");
+    assert_eq!(err.expected(), "u64");
+}
+
+#[test]
+fn empty_lists() {
+    assert_eq!("Starting items: ".parse::<Items>().unwrap().items, []);
+    assert_eq!(
+        "root -> ".parse::<Program>().unwrap(),
+        Program { name: "root".into(), children: Vec::new() },
+    );
+}
+
+#[test]
+fn absent_lists() {
+    assert_eq!(
+        "leaf".parse::<Program>().unwrap(),
+        Program { name: "leaf".into(), children: Vec::new() },
+    );
+    assert_eq!(
+        "root -> a, b".parse::<Program>().unwrap(),
+        Program { name: "root".into(), children: vec!["a".into(), "b".into()] },
+    );
+}
@@ -0,0 +1,75 @@
+use utils::{re, CapturesExt};
+
+#[test]
+fn pattern_only() {
+    assert!(re!(r"^\d+$").is_match("123"));
+    assert!(!re!(r"^\d+$").is_match("12a"));
+}
+
+#[test]
+fn captures() {
+    let captures = re!(r"(?P<a>\d+)-(?P<b>\d+)", "2-4");
+
+    assert_eq!((captures.parse::<u32>("a"), captures.parse::<u32>("b")), (2, 4));
+}
+
+#[test]
+fn closure() {
+    let (amount, from) = re!(r"move (?P<amount>\d+) from (?P<from>\d+)", "move 3 from 1", |cap| {
+        (cap.parse::<usize>("amount"), cap.str("from"))
+    });
+
+    assert_eq!((amount, from), (3, "1"));
+}
+
+#[test]
+fn typed_closure_parameter() {
+    let name = re!(r"Valve (?P<name>\w+)", "Valve AA", |cap: &utils::Captures| cap.try_str("name").unwrap());
+
+    assert_eq!(name, "AA");
+}
+
+/// Unrelated `str` calls inside the closure aren't mistaken for group names
+struct Lookup;
+
+impl Lookup {
+    fn str(&self, key: &str) -> usize {
+        key.len()
+    }
+}
+
+#[test]
+fn other_receivers_are_not_checked() {
+    let len = re!(r"(?P<word>\w+)", "abc", |cap| Lookup.str("not a group") + cap.str("word").len());
+
+    assert_eq!(len, 14);
+}
+
+const NUMBER: &str = r"(?P<n>\d+)";
+
+#[test]
+fn runtime_patterns() {
+    assert!(re!(NUMBER).is_match("42"));
+    assert_eq!(re!(NUMBER, "x 42").parse::<u32>("n"), 42);
+    assert_eq!(re!(NUMBER, "x 42", |cap| cap.parse::<u32>("n") + 1), 43);
+    assert_eq!(re!(concat!(r"^", r"(?P<n>\d+)", "$"), "7", |cap| cap.str("n")), "7");
+}
+
+#[test]
+#[should_panic(expected = "group \"missing\" did not participate")]
+fn runtime_patterns_check_group_names_at_runtime() {
+    re!(NUMBER, "42", |cap| cap.str("missing").len());
+}
+
+#[test]
+fn shadowed_captures_are_not_checked() {
+    let total = re!(r"(?P<a>\w+) (?P<b>\w+)", "xy z", |cap| {
+        let outer = cap.str("a").len();
+        let inner = re!(r"(?P<word>\w+)", "abc", |cap| cap.str("word").len());
+        let cap = re!(r"(?P<rebound>\w+)", "de");
+
+        outer + inner + cap.str("rebound").len()
+    });
+
+    assert_eq!(total, 7);
+}
@@ -0,0 +1,111 @@
+use utils::{scan, try_scan};
+
+#[test]
+fn day01_calories() {
+    assert_eq!(scan!("{}", "6000" => u32), (6000,));
+}
+
+#[test]
+fn day02_strategy() {
+    assert_eq!(scan!("{} {}", "A Y" => char, char), ('A', 'Y'));
+}
+
+#[test]
+fn day04_ranges() {
+    assert_eq!(scan!("{}-{},{}-{}", "2-4,6-8" => u32, u32, u32, u32), (2, 4, 6, 8));
+    assert_eq!(scan!("{}-{}", "12-345" => u32, u32), (12, 345));
+}
+
+#[test]
+fn day05_moves() {
+    assert_eq!(scan!("move {} from {} to {}", "move 13 from 1 to 2" => usize, usize, usize), (13, 1, 2));
+}
+
+#[test]
+fn day07_terminal() {
+    assert_eq!(scan!("$ cd {}", "$ cd a.b" => String), ("a.b".to_string(),));
+    assert_eq!(scan!("$ cd {}", "$ cd /" => String), ("/".to_string(),));
+    assert_eq!(scan!("dir {}", "dir d" => String), ("d".to_string(),));
+    assert_eq!(scan!("{} {}", "14848514 b.txt" => u64, String), (14848514, "b.txt".to_string()));
+    assert!(try_scan!("$ cd {}", "$ ls" => String).is_err());
+    assert!(try_scan!("$ ls", "$ ls" =>).is_ok());
+}
+
+#[test]
+fn day09_motions() {
+    assert_eq!(scan!("{} {}", "R 4" => char, u32), ('R', 4));
+}
+
+#[test]
+fn day10_instructions() {
+    assert_eq!(scan!("addx {}", "addx -11" => i32), (-11,));
+    assert!(try_scan!("noop", "noop" =>).is_ok());
+    assert!(try_scan!("addx {}", "noop" => i32).is_err());
+}
+
+#[test]
+fn day11_monkeys() {
+    assert_eq!(scan!("Monkey {}:", "Monkey 0:" => usize), (0,));
+    assert_eq!(
+        scan!("Starting items: {}", "  Starting items: 79, 98" => String),
+        ("79, 98".to_string(),),
+    );
+    assert_eq!(
+        scan!("Operation: new = old {} {}", "  Operation: new = old * old" => char, String),
+        ('*', "old".to_string()),
+    );
+    assert_eq!(scan!("Test: divisible by {}", "  Test: divisible by 23" => u64), (23,));
+    assert_eq!(scan!("If true: throw to monkey {}", "    If true: throw to monkey 2" => usize), (2,));
+}
+
+#[test]
+fn whitespace_is_tolerated() {
+    assert_eq!(scan!("move {} from {} to {}", "  move 3   from\t1 to  2  " => u8, u8, u8), (3, 1, 2));
+    assert_eq!(scan!("{} {}", "R    4" => char, u32), ('R', 4));
+    assert!(try_scan!("move {} from {} to {}", "move 3 from1 to 2" => u8, u8, u8).is_err());
+}
+
+#[test]
+fn escaped_braces() {
+    assert_eq!(scan!("{{{}}}", "{42}" => u32), (42,));
+    assert_eq!(scan!("{{x={}, y={}}}", "{x=-1, y=2}" => i32, i32), (-1, 2));
+    assert!(try_scan!("{{{}}}", "42" => u32).is_err());
+}
+
+#[test]
+fn mismatches() {
+    let err = try_scan!("move {} from {} to {}", "move 3 from 1" => u8, u8, u8).unwrap_err();
+
+    assert_eq!(err.text(), "move 3 from 1");
+    assert_eq!(err.expected(), r#"a line matching "move {} from {} to {}""#);
+
+    assert!(try_scan!("move {} from {} to {}", "move 3 from 1 to 2 now" => u8, u8, u8).is_err());
+    assert!(try_scan!("move {} from {} to {}", "please move 3 from 1 to 2" => u8, u8, u8).is_err());
+    assert!(try_scan!("{}-{}", "2-" => u32, u32).is_err());
+}
+
+#[test]
+fn field_errors_name_the_type() {
+    let err = try_scan!("{}-{}", "2-x" => u32, u32).unwrap_err();
+
+    assert_eq!(err.text(), "x");
+    assert_eq!(err.expected(), "u32");
+    assert!(err.reason().is_some());
+
+    assert!(try_scan!("{}", "300" => u8).is_err());
+    assert!(try_scan!("{} {}", "AB Y" => char, char).is_err());
+}
+
+#[test]
+#[should_panic(expected = "expected u32, found \"x\"")]
+fn scan_panics_on_mismatch() {
+    scan!("{}-{}", "2-x" => u32, u32);
+}
+
+#[test]
+fn errors_are_located_within_the_input() {
+    let input = "move 1 from 2 to 3\nmove x from 1 to 2";
+    let err = utils::try_parse_lines(input, |line| try_scan!("move {} from {} to {}", line => u8, u8, u8)).unwrap_err();
+
+    assert_eq!(err.to_string(), r#"line 2, column 6: expected u8, found "x" (invalid digit found in string)"#);
+}
@@ -0,0 +1,9 @@
+use utils::FromRegex;
+
+#[derive(FromRegex)]
+#[re(r"move (?P<amount>\d+")]
+struct Instruction {
+    amount: usize,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use utils::FromRegex;
+
+#[derive(FromRegex)]
+struct Instruction {
+    amount: usize,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use utils::FromRegex;
+
+#[derive(FromRegex)]
+#[re(r"items: (?P<items>.*)")]
+struct Items {
+    items: Vec<u64>,
+}
+
+fn main() {}
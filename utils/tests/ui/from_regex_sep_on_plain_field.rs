@@ -0,0 +1,10 @@
+use utils::FromRegex;
+
+#[derive(FromRegex)]
+#[re(r"item: (?P<item>.*)")]
+struct Item {
+    #[re(sep = ", ")]
+    item: u64,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use utils::FromRegex;
+
+#[derive(FromRegex)]
+#[re(r"move (?P<amount>\d+)")]
+struct Instruction {
+    amount: usize,
+    from: usize,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use utils::StrExt;
+
+fn main() {
+    "x=-2".uints::<i32>().for_each(drop);
+    "x=-2".ints::<u32>().for_each(drop);
+}
@@ -0,0 +1,5 @@
+use utils::re;
+
+fn main() {
+    re!(r"move (\d+");
+}
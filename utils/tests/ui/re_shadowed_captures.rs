@@ -0,0 +1,18 @@
+use utils::re;
+
+fn main() {
+    // Only the last call is on the closure's own captures
+    re!(r"(?P<outer>\w+) (?P<inner>\w+)", "a b", |cap| {
+        let words = re!(r"(?P<word>\w+)", "c", |cap| cap.str("word").len());
+        let first = {
+            let cap = re!(r"(?P<first>\w+)", "d");
+            cap.str("first").len()
+        };
+        let other = match Some(re!(r"(?P<other>\w+)", "e")) {
+            Some(cap) => cap.str("other").len(),
+            None => 0,
+        };
+
+        words + first + other + cap.str("inner").len() + cap.str("missing").len()
+    });
+}
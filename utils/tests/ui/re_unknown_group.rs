@@ -0,0 +1,5 @@
+use utils::re;
+
+fn main() {
+    re!(r"move (?P<amount>\d+)", "move 1", |cap| cap.str("nope"));
+}
@@ -0,0 +1,5 @@
+use utils::scan;
+
+fn main() {
+    scan!("{}{}", "12" => u8, u8);
+}
@@ -0,0 +1,5 @@
+use utils::scan;
+
+fn main() {
+    scan!("{x}", "12" => u8);
+}
@@ -0,0 +1,5 @@
+use utils::scan;
+
+fn main() {
+    scan!("{}-{}", "1-2" => u8);
+}